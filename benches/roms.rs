@@ -0,0 +1,50 @@
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::fs;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use chip8_emulator::{Config, Machine};
+
+// RUNS EVERY ROM OF data/ HEADLESS, WITHOUT AND WITH THE DECODE CACHE: cargo bench --bench roms
+// TO MEASURE A CHANGE, SAVE A BASELINE BEFORE IT WITH -- --save-baseline before AND COMPARE
+// AFTER IT WITH -- --baseline before
+const FRAMES: usize = 600;
+const INSTRUCTIONS_PER_FRAME: usize = 1000;
+
+fn run(rom: &[u8], config: Config) -> Machine {
+    let mut machine = common::machine(config, rom);
+    let _ = common::run_frames(&mut machine, FRAMES);
+    machine
+}
+
+fn roms(c: &mut Criterion) {
+    let mut paths: Vec<_> = fs::read_dir("data").unwrap().map(|entry| entry.unwrap().path()).collect();
+    paths.sort();
+    let roms: Vec<(String, Vec<u8>)> = paths
+        .iter()
+        .map(|path| (path.file_name().unwrap().to_string_lossy().into_owned(), fs::read(path).unwrap()))
+        .collect();
+
+    let config = Config {
+        instructions_per_frame: INSTRUCTIONS_PER_FRAME,
+        // AN IDLE LOOP WOULD HIDE WHAT IS MEASURED
+        skip_idle_loops: false,
+        ..Config::default()
+    };
+    let runs = [
+        ("uncached", Config { decode_cache: false, ..config }),
+        ("cached", config),
+    ];
+    for (name, config) in runs {
+        let mut group = c.benchmark_group(name);
+        group.sample_size(10);
+        group.throughput(Throughput::Elements((FRAMES * INSTRUCTIONS_PER_FRAME) as u64));
+        for (rom_name, rom) in &roms {
+            group.bench_with_input(BenchmarkId::from_parameter(rom_name), rom, |b, rom| b.iter(|| run(rom, config)));
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, roms);
+criterion_main!(benches);
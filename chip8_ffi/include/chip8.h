@@ -0,0 +1,199 @@
+#ifndef CHIP8_H
+#define CHIP8_H
+
+/* Generated by cbindgen from chip8_ffi/src/lib.rs, do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Result of the calls that can fail. A panic inside the emulator is reported as
+ * `CpuFault` rather than unwinding into the caller.
+ */
+typedef enum Chip8Status {
+  CHIP8_STATUS_OK = 0,
+  CHIP8_STATUS_NULL_POINTER = 1,
+  CHIP8_STATUS_OUT_OF_RANGE = 2,
+  CHIP8_STATUS_INVALID_STATE = 3,
+  CHIP8_STATUS_CPU_FAULT = 4,
+} Chip8Status;
+
+/**
+ * Opaque handle on an emulated machine.
+ */
+typedef struct Chip8 Chip8;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates a machine, or returns NULL if that failed. Free it with `chip8_free`.
+ */
+struct Chip8 *chip8_new(void);
+
+/**
+ * # Safety
+ * `handle` must come from `chip8_new` and not be used afterwards. NULL is ignored.
+ */
+void chip8_free(struct Chip8 *handle);
+
+/**
+ * Resets the machine and loads `len` bytes of ROM at 0x200. The seed given to
+ * `chip8_set_random_seed` is kept.
+ *
+ * # Safety
+ * `handle` must be a live machine and `data` must point to `len` readable bytes.
+ */
+enum Chip8Status chip8_load_rom(struct Chip8 *handle, const uint8_t *data, size_t len);
+
+/**
+ * Seeds the generator used by CXNN, for this machine and the ones later
+ * `chip8_load_rom` calls start.
+ *
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+void chip8_set_random_seed(struct Chip8 *handle, uint32_t seed);
+
+/**
+ * Executes a single instruction, without ticking the timers.
+ *
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+enum Chip8Status chip8_step(struct Chip8 *handle);
+
+/**
+ * Executes one 60 Hz frame: a batch of instructions and one timer tick.
+ *
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+enum Chip8Status chip8_run_frame(struct Chip8 *handle);
+
+/**
+ * Sets the whole keypad, bit N being key N.
+ *
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+void chip8_set_keys(struct Chip8 *handle, uint16_t mask);
+
+/**
+ * Returns the framebuffer, one byte (0 or 1) per pixel, row by row.
+ * The pointer stays valid until the next call that mutates the machine.
+ *
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+const uint8_t *chip8_framebuffer(const struct Chip8 *handle);
+
+size_t chip8_framebuffer_width(void);
+
+size_t chip8_framebuffer_height(void);
+
+/**
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+bool chip8_sound_active(const struct Chip8 *handle);
+
+/**
+ * Reads VX. Returns 0 for a NULL handle or X above 0xF.
+ *
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+uint8_t chip8_get_register(const struct Chip8 *handle, uint8_t x);
+
+/**
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+enum Chip8Status chip8_set_register(struct Chip8 *handle, uint8_t x, uint8_t value);
+
+/**
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+uint16_t chip8_get_pc(const struct Chip8 *handle);
+
+/**
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+void chip8_set_pc(struct Chip8 *handle, uint16_t pc);
+
+/**
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+uint16_t chip8_get_i(const struct Chip8 *handle);
+
+/**
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+void chip8_set_i(struct Chip8 *handle, uint16_t i);
+
+/**
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+uint8_t chip8_get_delay_timer(const struct Chip8 *handle);
+
+/**
+ * # Safety
+ * `handle` must be a live machine or NULL.
+ */
+uint8_t chip8_get_sound_timer(const struct Chip8 *handle);
+
+/**
+ * Copies `len` bytes of memory starting at `address` into `out`.
+ *
+ * # Safety
+ * `handle` must be a live machine and `out` must point to `len` writable bytes.
+ */
+enum Chip8Status chip8_read_memory(const struct Chip8 *handle,
+                                   uint16_t address,
+                                   uint8_t *out,
+                                   size_t len);
+
+/**
+ * Copies `len` bytes from `data` into memory starting at `address`.
+ *
+ * # Safety
+ * `handle` must be a live machine and `data` must point to `len` readable bytes.
+ */
+enum Chip8Status chip8_write_memory(struct Chip8 *handle,
+                                    uint16_t address,
+                                    const uint8_t *data,
+                                    size_t len);
+
+/**
+ * Serializes the machine into `out`. Returns the size of the state; nothing is
+ * written when `capacity` is smaller than that, so call it with a NULL `out` first
+ * to size the buffer.
+ *
+ * # Safety
+ * `handle` must be a live machine and `out` must point to `capacity` writable bytes or be NULL.
+ */
+size_t chip8_save_state(const struct Chip8 *handle, uint8_t *out, size_t capacity);
+
+/**
+ * Restores a state written by `chip8_save_state`. The machine is unchanged on error.
+ *
+ * # Safety
+ * `handle` must be a live machine and `data` must point to `len` readable bytes.
+ */
+enum Chip8Status chip8_load_state(struct Chip8 *handle, const uint8_t *data, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CHIP8_H */
@@ -0,0 +1,372 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use chip8_emulator::chip8::{MAX_ROM_SIZE, MEMORY_SIZE};
+use chip8_emulator::display::{HEIGHT_SCREEN, WIDTH_SCREEN};
+use chip8_emulator::Chip8Error;
+
+/// Opaque handle on an emulated machine.
+pub struct Chip8 {
+    chip8: chip8_emulator::Chip8,
+    // KEPT FOR THE FRESH MACHINE chip8_load_rom STARTS
+    seed: Option<u32>,
+}
+
+/// Result of the calls that can fail. A panic inside the emulator is reported as
+/// `CpuFault` rather than unwinding into the caller.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Chip8Status {
+    Ok = 0,
+    NullPointer = 1,
+    OutOfRange = 2,
+    InvalidState = 3,
+    CpuFault = 4,
+}
+
+// RUNS AN ENTRY POINT, RETURNING fault INSTEAD OF UNWINDING INTO C WHEN THE CORE PANICS
+fn guard<T>(fault: T, f: impl FnOnce() -> T) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(fault)
+}
+
+// RUNS THE CORE, TURNING AN ERROR OF THE PROGRAM OR A PANIC INTO A STATUS
+fn run_guarded<F>(handle: *mut Chip8, f: F) -> Chip8Status
+where
+    F: FnOnce(&mut chip8_emulator::Chip8) -> Result<(), Chip8Error>,
+{
+    let Some(handle) = (unsafe { handle.as_mut() }) else {
+        return Chip8Status::NullPointer;
+    };
+    guard(Chip8Status::CpuFault, || match f(&mut handle.chip8) {
+        Ok(()) => Chip8Status::Ok,
+        Err(_) => Chip8Status::CpuFault,
+    })
+}
+
+fn in_memory(address: u16, len: usize) -> bool {
+    (address as usize).checked_add(len).is_some_and(|end| end <= MEMORY_SIZE)
+}
+
+/// Creates a machine, or returns NULL if that failed. Free it with `chip8_free`.
+#[no_mangle]
+pub extern "C" fn chip8_new() -> *mut Chip8 {
+    guard(ptr::null_mut(), || {
+        Box::into_raw(Box::new(Chip8 {
+            chip8: chip8_emulator::Chip8::new(),
+            seed: None,
+        }))
+    })
+}
+
+/// # Safety
+/// `handle` must come from `chip8_new` and not be used afterwards. NULL is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_free(handle: *mut Chip8) {
+    guard((), || {
+        if !handle.is_null() {
+            drop(Box::from_raw(handle));
+        }
+    })
+}
+
+/// Resets the machine and loads `len` bytes of ROM at 0x200. The seed given to
+/// `chip8_set_random_seed` is kept.
+///
+/// # Safety
+/// `handle` must be a live machine and `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_load_rom(handle: *mut Chip8, data: *const u8, len: usize) -> Chip8Status {
+    guard(Chip8Status::CpuFault, || {
+        if handle.is_null() || data.is_null() {
+            return Chip8Status::NullPointer;
+        }
+        if len > MAX_ROM_SIZE {
+            return Chip8Status::OutOfRange;
+        }
+        let rom = slice::from_raw_parts(data, len);
+        let handle = &mut *handle;
+        handle.chip8 = chip8_emulator::Chip8::new();
+        if let Some(seed) = handle.seed {
+            handle.chip8.set_random_seed(seed);
+        }
+        match handle.chip8.load_rom(rom) {
+            Ok(()) => Chip8Status::Ok,
+            Err(_) => Chip8Status::OutOfRange,
+        }
+    })
+}
+
+/// Seeds the generator used by CXNN, for this machine and the ones later
+/// `chip8_load_rom` calls start.
+///
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_set_random_seed(handle: *mut Chip8, seed: u32) {
+    guard((), || {
+        if let Some(handle) = handle.as_mut() {
+            handle.seed = Some(seed);
+            handle.chip8.set_random_seed(seed);
+        }
+    })
+}
+
+/// Executes a single instruction, without ticking the timers.
+///
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_step(handle: *mut Chip8) -> Chip8Status {
+    run_guarded(handle, |chip8| chip8.run_instruction())
+}
+
+/// Executes one 60 Hz frame: a batch of instructions and one timer tick.
+///
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_run_frame(handle: *mut Chip8) -> Chip8Status {
+    run_guarded(handle, |chip8| chip8.run_frame())
+}
+
+/// Sets the whole keypad, bit N being key N.
+///
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_set_keys(handle: *mut Chip8, mask: u16) {
+    guard((), || {
+        if let Some(handle) = handle.as_mut() {
+            handle.chip8.change_keys_pressed(mask);
+        }
+    })
+}
+
+/// Returns the framebuffer, one byte (0 or 1) per pixel, row by row.
+/// The pointer stays valid until the next call that mutates the machine.
+///
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_framebuffer(handle: *const Chip8) -> *const u8 {
+    guard(ptr::null(), || {
+        match handle.as_ref() {
+            Some(handle) => handle.chip8.get_display().as_ptr(),
+            None => ptr::null(),
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn chip8_framebuffer_width() -> usize {
+    guard(0, || {
+        WIDTH_SCREEN
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn chip8_framebuffer_height() -> usize {
+    guard(0, || {
+        HEIGHT_SCREEN
+    })
+}
+
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_sound_active(handle: *const Chip8) -> bool {
+    guard(false, || {
+        handle.as_ref().is_some_and(|handle| handle.chip8.is_sound_active())
+    })
+}
+
+/// Reads VX. Returns 0 for a NULL handle or X above 0xF.
+///
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_get_register(handle: *const Chip8, x: u8) -> u8 {
+    guard(0, || {
+        match handle.as_ref() {
+            Some(handle) if x < 16 => handle.chip8.get_registers()[x as usize],
+            _ => 0,
+        }
+    })
+}
+
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_set_register(handle: *mut Chip8, x: u8, value: u8) -> Chip8Status {
+    guard(Chip8Status::CpuFault, || {
+        let Some(handle) = handle.as_mut() else {
+            return Chip8Status::NullPointer;
+        };
+        if x >= 16 {
+            return Chip8Status::OutOfRange;
+        }
+        handle.chip8.change_register(x, value);
+        Chip8Status::Ok
+    })
+}
+
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_get_pc(handle: *const Chip8) -> u16 {
+    guard(0, || {
+        handle.as_ref().map_or(0, |handle| handle.chip8.get_pc())
+    })
+}
+
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_set_pc(handle: *mut Chip8, pc: u16) {
+    guard((), || {
+        if let Some(handle) = handle.as_mut() {
+            handle.chip8.change_pc(pc);
+        }
+    })
+}
+
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_get_i(handle: *const Chip8) -> u16 {
+    guard(0, || {
+        handle.as_ref().map_or(0, |handle| handle.chip8.get_i())
+    })
+}
+
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_set_i(handle: *mut Chip8, i: u16) {
+    guard((), || {
+        if let Some(handle) = handle.as_mut() {
+            handle.chip8.change_i(i);
+        }
+    })
+}
+
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_get_delay_timer(handle: *const Chip8) -> u8 {
+    guard(0, || {
+        handle.as_ref().map_or(0, |handle| handle.chip8.get_delay_timer())
+    })
+}
+
+/// # Safety
+/// `handle` must be a live machine or NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_get_sound_timer(handle: *const Chip8) -> u8 {
+    guard(0, || {
+        handle.as_ref().map_or(0, |handle| handle.chip8.get_sound_timer())
+    })
+}
+
+/// Copies `len` bytes of memory starting at `address` into `out`.
+///
+/// # Safety
+/// `handle` must be a live machine and `out` must point to `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_read_memory(
+    handle: *const Chip8,
+    address: u16,
+    out: *mut u8,
+    len: usize,
+) -> Chip8Status {
+    guard(Chip8Status::CpuFault, || {
+        let Some(handle) = handle.as_ref() else {
+            return Chip8Status::NullPointer;
+        };
+        if out.is_null() {
+            return Chip8Status::NullPointer;
+        }
+        if !in_memory(address, len) {
+            return Chip8Status::OutOfRange;
+        }
+        let out = slice::from_raw_parts_mut(out, len);
+        for (offset, byte) in out.iter_mut().enumerate() {
+            match handle.chip8.read_memory(address + offset as u16) {
+                Ok(value) => *byte = value,
+                Err(_) => return Chip8Status::OutOfRange,
+            }
+        }
+        Chip8Status::Ok
+    })
+}
+
+/// Copies `len` bytes from `data` into memory starting at `address`.
+///
+/// # Safety
+/// `handle` must be a live machine and `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_write_memory(
+    handle: *mut Chip8,
+    address: u16,
+    data: *const u8,
+    len: usize,
+) -> Chip8Status {
+    guard(Chip8Status::CpuFault, || {
+        let Some(handle) = handle.as_mut() else {
+            return Chip8Status::NullPointer;
+        };
+        if data.is_null() {
+            return Chip8Status::NullPointer;
+        }
+        if !in_memory(address, len) {
+            return Chip8Status::OutOfRange;
+        }
+        let data = slice::from_raw_parts(data, len);
+        for (offset, byte) in data.iter().enumerate() {
+            if handle.chip8.write_memory(address + offset as u16, *byte).is_err() {
+                return Chip8Status::OutOfRange;
+            }
+        }
+        Chip8Status::Ok
+    })
+}
+
+/// Serializes the machine into `out`. Returns the size of the state; nothing is
+/// written when `capacity` is smaller than that, so call it with a NULL `out` first
+/// to size the buffer.
+///
+/// # Safety
+/// `handle` must be a live machine and `out` must point to `capacity` writable bytes or be NULL.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_save_state(handle: *const Chip8, out: *mut u8, capacity: usize) -> usize {
+    guard(0, || {
+        let Some(handle) = handle.as_ref() else {
+            return 0;
+        };
+        let state = handle.chip8.save_state();
+        if !out.is_null() && capacity >= state.len() {
+            ptr::copy_nonoverlapping(state.as_ptr(), out, state.len());
+        }
+        state.len()
+    })
+}
+
+/// Restores a state written by `chip8_save_state`. The machine is unchanged on error.
+///
+/// # Safety
+/// `handle` must be a live machine and `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn chip8_load_state(handle: *mut Chip8, data: *const u8, len: usize) -> Chip8Status {
+    guard(Chip8Status::CpuFault, || {
+        let Some(handle) = handle.as_mut() else {
+            return Chip8Status::NullPointer;
+        };
+        if data.is_null() {
+            return Chip8Status::NullPointer;
+        }
+        match handle.chip8.load_state(slice::from_raw_parts(data, len)) {
+            Ok(()) => Chip8Status::Ok,
+            Err(_) => Chip8Status::InvalidState,
+        }
+    })
+}
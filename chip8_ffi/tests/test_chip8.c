@@ -0,0 +1,127 @@
+/* SMOKE TEST OF THE C API, BUILT AND RUN BY `make -C chip8_ffi test` */
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include "chip8.h"
+
+#define CHECK(cond)                                                        \
+    do {                                                                   \
+        if (!(cond)) {                                                     \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+            return 1;                                                      \
+        }                                                                  \
+    } while (0)
+
+static uint8_t *read_file(const char *path, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    if (!file) {
+        return NULL;
+    }
+    fseek(file, 0, SEEK_END);
+    long size = ftell(file);
+    fseek(file, 0, SEEK_SET);
+    uint8_t *data = malloc((size_t)size);
+    *len = fread(data, 1, (size_t)size, file);
+    fclose(file);
+    return data;
+}
+
+static int count_pixels(const Chip8 *chip8) {
+    const uint8_t *framebuffer = chip8_framebuffer(chip8);
+    size_t size = chip8_framebuffer_width() * chip8_framebuffer_height();
+    int lit = 0;
+    for (size_t i = 0; i < size; i++) {
+        lit += framebuffer[i];
+    }
+    return lit;
+}
+
+int main(int argc, char **argv) {
+    const char *rom_path = argc > 1 ? argv[1] : "../data/BRIX";
+
+    Chip8 *chip8 = chip8_new();
+    CHECK(chip8 != NULL);
+    CHECK(chip8_framebuffer_width() == 64);
+    CHECK(chip8_framebuffer_height() == 32);
+
+    /* A TINY PROGRAM: V0 = 0x2A, I = 0x300, THEN JUMP TO ITSELF */
+    const uint8_t program[] = {0x60, 0x2A, 0xA3, 0x00, 0x12, 0x04};
+    CHECK(chip8_load_rom(chip8, program, sizeof program) == CHIP8_STATUS_OK);
+    CHECK(chip8_get_pc(chip8) == 0x200);
+    CHECK(chip8_step(chip8) == CHIP8_STATUS_OK);
+    CHECK(chip8_step(chip8) == CHIP8_STATUS_OK);
+    CHECK(chip8_get_register(chip8, 0) == 0x2A);
+    CHECK(chip8_get_i(chip8) == 0x300);
+    CHECK(chip8_get_pc(chip8) == 0x204);
+
+    /* REGISTERS AND MEMORY ROUND TRIP */
+    CHECK(chip8_set_register(chip8, 5, 0x77) == CHIP8_STATUS_OK);
+    CHECK(chip8_get_register(chip8, 5) == 0x77);
+    CHECK(chip8_set_register(chip8, 16, 0) == CHIP8_STATUS_OUT_OF_RANGE);
+    const uint8_t bytes[] = {1, 2, 3, 4};
+    uint8_t read_back[4] = {0};
+    CHECK(chip8_write_memory(chip8, 0x400, bytes, sizeof bytes) == CHIP8_STATUS_OK);
+    CHECK(chip8_read_memory(chip8, 0x400, read_back, sizeof read_back) == CHIP8_STATUS_OK);
+    CHECK(memcmp(bytes, read_back, sizeof bytes) == 0);
+    CHECK(chip8_read_memory(chip8, 0xFFE, read_back, 4) == CHIP8_STATUS_OUT_OF_RANGE);
+
+    /* THE SEED IS KEPT WHEN A ROM IS LOADED: V0 = RANDOM, THEN JUMP TO ITSELF */
+    const uint8_t random[] = {0xC0, 0xFF, 0x12, 0x02};
+    struct Chip8 *seeded_first = chip8_new();
+    chip8_set_random_seed(seeded_first, 1234);
+    CHECK(chip8_load_rom(seeded_first, random, sizeof random) == CHIP8_STATUS_OK);
+    CHECK(chip8_step(seeded_first) == CHIP8_STATUS_OK);
+    struct Chip8 *seeded_after = chip8_new();
+    CHECK(chip8_load_rom(seeded_after, random, sizeof random) == CHIP8_STATUS_OK);
+    chip8_set_random_seed(seeded_after, 1234);
+    CHECK(chip8_step(seeded_after) == CHIP8_STATUS_OK);
+    CHECK(chip8_get_register(seeded_first, 0) == chip8_get_register(seeded_after, 0));
+    chip8_free(seeded_first);
+    chip8_free(seeded_after);
+
+    /* AN UNKNOWN OPCODE FAULTS INSTEAD OF ABORTING THE PROCESS */
+    const uint8_t unknown[] = {0xFF, 0xFF};
+    CHECK(chip8_load_rom(chip8, unknown, sizeof unknown) == CHIP8_STATUS_OK);
+    CHECK(chip8_step(chip8) == CHIP8_STATUS_CPU_FAULT);
+    CHECK(chip8_run_frame(chip8) == CHIP8_STATUS_CPU_FAULT);
+    CHECK(chip8_step(NULL) == CHIP8_STATUS_NULL_POINTER);
+
+    /* RUN A REAL GAME FOR A SECOND */
+    size_t rom_len = 0;
+    uint8_t *rom = read_file(rom_path, &rom_len);
+    CHECK(rom != NULL);
+    CHECK(chip8_load_rom(chip8, rom, rom_len) == CHIP8_STATUS_OK);
+    free(rom);
+    for (int frame = 0; frame < 60; frame++) {
+        CHECK(chip8_run_frame(chip8) == CHIP8_STATUS_OK);
+    }
+    CHECK(count_pixels(chip8) > 0);
+
+    /* SAVE STATE, RUN, RESTORE AND CHECK THE FRAMES MATCH */
+    size_t state_len = chip8_save_state(chip8, NULL, 0);
+    CHECK(state_len > 0);
+    uint8_t *state = malloc(state_len);
+    CHECK(chip8_save_state(chip8, state, state_len) == state_len);
+    chip8_set_keys(chip8, 1u << 4);
+    for (int frame = 0; frame < 30; frame++) {
+        CHECK(chip8_run_frame(chip8) == CHIP8_STATUS_OK);
+    }
+    uint8_t expected[64 * 32];
+    memcpy(expected, chip8_framebuffer(chip8), sizeof expected);
+
+    Chip8 *restored = chip8_new();
+    CHECK(chip8_load_state(restored, state, state_len) == CHIP8_STATUS_OK);
+    chip8_set_keys(restored, 1u << 4);
+    for (int frame = 0; frame < 30; frame++) {
+        CHECK(chip8_run_frame(restored) == CHIP8_STATUS_OK);
+    }
+    CHECK(memcmp(expected, chip8_framebuffer(restored), sizeof expected) == 0);
+    CHECK(chip8_load_state(restored, state, 3) == CHIP8_STATUS_INVALID_STATE);
+    free(state);
+
+    chip8_free(restored);
+    chip8_free(chip8);
+    chip8_free(NULL);
+    printf("test_chip8: ok\n");
+    return 0;
+}
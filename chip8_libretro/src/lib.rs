@@ -0,0 +1,455 @@
+pub mod libretro;
+
+use std::ffi::CStr;
+use std::fs;
+use std::os::raw::{c_char, c_uint, c_void};
+use std::ptr;
+use std::slice;
+use std::sync::Mutex;
+use chip8_emulator::display::{HEIGHT_SCREEN, WIDTH_SCREEN};
+use chip8_emulator::{Chip8, Quirks};
+use libretro::*;
+
+const SAMPLE_RATE: u32 = 44_100;
+const SAMPLES_PER_FRAME: usize = SAMPLE_RATE as usize / 60;
+const BEEP_FREQUENCY: u32 = 440;
+const BEEP_VOLUME: i16 = 4_000;
+
+// JOYPAD BUTTON -> CHIP-8 KEY. DIRECTIONS ARE 2/4/6/8 AND A IS 5, LIKE MOST GAMES EXPECT
+const JOYPAD_MAPPING: [(c_uint, u8); 12] = [
+    (RETRO_DEVICE_ID_JOYPAD_UP, 0x2),
+    (RETRO_DEVICE_ID_JOYPAD_DOWN, 0x8),
+    (RETRO_DEVICE_ID_JOYPAD_LEFT, 0x4),
+    (RETRO_DEVICE_ID_JOYPAD_RIGHT, 0x6),
+    (RETRO_DEVICE_ID_JOYPAD_A, 0x5),
+    (RETRO_DEVICE_ID_JOYPAD_B, 0x0),
+    (RETRO_DEVICE_ID_JOYPAD_X, 0x1),
+    (RETRO_DEVICE_ID_JOYPAD_Y, 0x3),
+    (RETRO_DEVICE_ID_JOYPAD_L, 0x7),
+    (RETRO_DEVICE_ID_JOYPAD_R, 0x9),
+    (RETRO_DEVICE_ID_JOYPAD_SELECT, 0xA),
+    (RETRO_DEVICE_ID_JOYPAD_START, 0xF),
+];
+
+// KEYBOARD (RETROK CODES ARE ASCII) -> CHIP-8 KEY, THE USUAL QWERTY LAYOUT:
+// 1 2 3 4 / Q W E R / A S D F / Z X C V
+const KEYBOARD_MAPPING: [(u8, u8); 16] = [
+    (b'1', 0x1), (b'2', 0x2), (b'3', 0x3), (b'4', 0xC),
+    (b'q', 0x4), (b'w', 0x5), (b'e', 0x6), (b'r', 0xD),
+    (b'a', 0x7), (b's', 0x8), (b'd', 0x9), (b'f', 0xE),
+    (b'z', 0xA), (b'x', 0x0), (b'c', 0xB), (b'v', 0xF),
+];
+
+// (FOREGROUND, BACKGROUND) IN XRGB8888
+const PALETTES: [(&str, u32, u32); 4] = [
+    ("white", 0xFFFFFF, 0x000000),
+    ("green", 0x33FF66, 0x0A1A0F),
+    ("amber", 0xFFB000, 0x1A1000),
+    ("lcd", 0x0F380F, 0x9BBC0F),
+];
+
+const OPTION_QUIRKS: &CStr = c"chip8_quirks";
+const OPTION_SPEED: &CStr = c"chip8_speed";
+const OPTION_PALETTE: &CStr = c"chip8_palette";
+
+struct Callbacks {
+    environment: Option<RetroEnvironmentFn>,
+    video_refresh: Option<RetroVideoRefreshFn>,
+    audio_sample_batch: Option<RetroAudioSampleBatchFn>,
+    input_poll: Option<RetroInputPollFn>,
+    input_state: Option<RetroInputStateFn>,
+}
+
+struct Core {
+    chip8: Chip8,
+    rom: Vec<u8>,
+    foreground: u32,
+    background: u32,
+    framebuffer: Vec<u32>,
+    audio: Vec<i16>,
+    audio_phase: u32,
+    // SET WHEN THE ROM CRASHED THE CPU, THE LAST FRAME STAYS ON SCREEN
+    halted: bool,
+}
+
+static CALLBACKS: Mutex<Callbacks> = Mutex::new(Callbacks {
+    environment: None,
+    video_refresh: None,
+    audio_sample_batch: None,
+    input_poll: None,
+    input_state: None,
+});
+
+static CORE: Mutex<Option<Core>> = Mutex::new(None);
+
+fn callbacks() -> std::sync::MutexGuard<'static, Callbacks> {
+    CALLBACKS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn core() -> std::sync::MutexGuard<'static, Option<Core>> {
+    CORE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+    match callbacks().environment {
+        Some(environment) => unsafe { environment(cmd, data) },
+        None => false,
+    }
+}
+
+fn get_variable(key: &CStr) -> Option<String> {
+    let mut variable = RetroVariable {
+        key: key.as_ptr(),
+        value: ptr::null(),
+    };
+    if !environment(RETRO_ENVIRONMENT_GET_VARIABLE, &mut variable as *mut _ as *mut c_void)
+        || variable.value.is_null()
+    {
+        return None;
+    }
+    let value = unsafe { CStr::from_ptr(variable.value) };
+    value.to_str().ok().map(String::from)
+}
+
+impl Core {
+    fn new(rom: Vec<u8>) -> Core {
+        let mut core = Core {
+            chip8: Chip8::new(),
+            rom,
+            foreground: PALETTES[0].1,
+            background: PALETTES[0].2,
+            framebuffer: vec![0; WIDTH_SCREEN * HEIGHT_SCREEN],
+            audio: vec![0; SAMPLES_PER_FRAME * 2],
+            audio_phase: 0,
+            halted: false,
+        };
+        core.reset();
+        core.apply_options();
+        core
+    }
+
+    fn reset(&mut self) {
+        self.chip8 = Chip8::with_config(*self.chip8.get_config());
+        // THE ROM SIZE IS CHECKED BY retro_load_game, A ROM THAT DOES NOT FIT HALTS THE CORE
+        self.halted = self.chip8.load_rom(&self.rom).is_err();
+    }
+
+    fn apply_options(&mut self) {
+        let mut config = *self.chip8.get_config();
+        if let Some(quirks) = get_variable(OPTION_QUIRKS).and_then(|name| Quirks::from_name(&name)) {
+            config.quirks = quirks;
+        }
+        if let Some(speed) = get_variable(OPTION_SPEED).and_then(|speed| speed.parse().ok()) {
+            config.instructions_per_frame = speed;
+        }
+        self.chip8.change_config(config);
+
+        if let Some(name) = get_variable(OPTION_PALETTE) {
+            if let Some((_, foreground, background)) = PALETTES.iter().find(|(n, _, _)| *n == name) {
+                self.foreground = *foreground;
+                self.background = *background;
+            }
+        }
+    }
+
+    fn read_keys(&self) -> u16 {
+        let callbacks = callbacks();
+        if let Some(input_poll) = callbacks.input_poll {
+            unsafe { input_poll() };
+        }
+        let Some(input_state) = callbacks.input_state else {
+            return 0;
+        };
+        let mut mask = 0;
+        for (id, key) in JOYPAD_MAPPING.iter() {
+            if unsafe { input_state(0, RETRO_DEVICE_JOYPAD, 0, *id) } != 0 {
+                mask |= 1 << key;
+            }
+        }
+        for (code, key) in KEYBOARD_MAPPING.iter() {
+            if unsafe { input_state(0, RETRO_DEVICE_KEYBOARD, 0, *code as c_uint) } != 0 {
+                mask |= 1 << key;
+            }
+        }
+        mask
+    }
+
+    fn run_frame(&mut self) {
+        let keys = self.read_keys();
+        self.chip8.change_keys_pressed(keys);
+        if !self.halted {
+            self.halted = self.chip8.run_frame().is_err();
+        }
+
+        for (pixel, color) in self.chip8.get_display().iter().zip(self.framebuffer.iter_mut()) {
+            *color = if *pixel == 1 { self.foreground } else { self.background };
+        }
+
+        // SQUARE WAVE WHILE THE SOUND TIMER RUNS
+        let sound_active = self.chip8.is_sound_active() && !self.halted;
+        let half_period = SAMPLE_RATE / BEEP_FREQUENCY / 2;
+        for frame in self.audio.chunks_mut(2) {
+            let sample = if !sound_active {
+                0
+            } else if (self.audio_phase / half_period).is_multiple_of(2) {
+                BEEP_VOLUME
+            } else {
+                -BEEP_VOLUME
+            };
+            frame[0] = sample;
+            frame[1] = sample;
+            self.audio_phase = self.audio_phase.wrapping_add(1);
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn retro_api_version() -> c_uint {
+    RETRO_API_VERSION
+}
+
+/// # Safety
+/// Called by the frontend with a valid callback.
+#[no_mangle]
+pub unsafe extern "C" fn retro_set_environment(callback: Option<RetroEnvironmentFn>) {
+    callbacks().environment = callback;
+
+    let variables = [
+        RetroVariable {
+            key: OPTION_QUIRKS.as_ptr(),
+            value: c"Quirks; legacy|chip8|schip".as_ptr(),
+        },
+        RetroVariable {
+            key: OPTION_SPEED.as_ptr(),
+            value: c"Instructions per frame; 10|15|20|30|50|100|1000|5|7".as_ptr(),
+        },
+        RetroVariable {
+            key: OPTION_PALETTE.as_ptr(),
+            value: c"Palette; white|green|amber|lcd".as_ptr(),
+        },
+        RetroVariable {
+            key: ptr::null(),
+            value: ptr::null(),
+        },
+    ];
+    environment(RETRO_ENVIRONMENT_SET_VARIABLES, variables.as_ptr() as *mut c_void);
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_video_refresh(callback: Option<RetroVideoRefreshFn>) {
+    callbacks().video_refresh = callback;
+}
+
+// THE CORE ONLY USES THE BATCH CALLBACK
+#[no_mangle]
+pub extern "C" fn retro_set_audio_sample(_callback: Option<RetroAudioSampleFn>) {}
+
+#[no_mangle]
+pub extern "C" fn retro_set_audio_sample_batch(callback: Option<RetroAudioSampleBatchFn>) {
+    callbacks().audio_sample_batch = callback;
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_input_poll(callback: Option<RetroInputPollFn>) {
+    callbacks().input_poll = callback;
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_input_state(callback: Option<RetroInputStateFn>) {
+    callbacks().input_state = callback;
+}
+
+#[no_mangle]
+pub extern "C" fn retro_init() {}
+
+#[no_mangle]
+pub extern "C" fn retro_deinit() {
+    *core() = None;
+}
+
+/// # Safety
+/// `info` must point to a writable `retro_system_info`.
+#[no_mangle]
+pub unsafe extern "C" fn retro_get_system_info(info: *mut RetroSystemInfo) {
+    if let Some(info) = info.as_mut() {
+        info.library_name = c"rust_chip8".as_ptr();
+        info.library_version = c"0.1.0".as_ptr();
+        info.valid_extensions = c"ch8|c8|rom".as_ptr();
+        info.need_fullpath = false;
+        info.block_extract = false;
+    }
+}
+
+/// # Safety
+/// `info` must point to a writable `retro_system_av_info`.
+#[no_mangle]
+pub unsafe extern "C" fn retro_get_system_av_info(info: *mut RetroSystemAvInfo) {
+    if let Some(info) = info.as_mut() {
+        info.geometry = RetroGameGeometry {
+            base_width: WIDTH_SCREEN as c_uint,
+            base_height: HEIGHT_SCREEN as c_uint,
+            max_width: WIDTH_SCREEN as c_uint,
+            max_height: HEIGHT_SCREEN as c_uint,
+            aspect_ratio: WIDTH_SCREEN as f32 / HEIGHT_SCREEN as f32,
+        };
+        info.timing = RetroSystemTiming {
+            fps: 60.0,
+            sample_rate: SAMPLE_RATE as f64,
+        };
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn retro_set_controller_port_device(_port: c_uint, _device: c_uint) {}
+
+#[no_mangle]
+pub extern "C" fn retro_reset() {
+    if let Some(core) = core().as_mut() {
+        core.reset();
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn retro_run() {
+    let mut updated = false;
+    if environment(RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE, &mut updated as *mut bool as *mut c_void)
+        && updated
+    {
+        if let Some(core) = core().as_mut() {
+            core.apply_options();
+        }
+    }
+
+    let mut core = core();
+    let Some(core) = core.as_mut() else {
+        return;
+    };
+    core.run_frame();
+
+    let callbacks = callbacks();
+    if let Some(video_refresh) = callbacks.video_refresh {
+        unsafe {
+            video_refresh(
+                core.framebuffer.as_ptr() as *const c_void,
+                WIDTH_SCREEN as c_uint,
+                HEIGHT_SCREEN as c_uint,
+                WIDTH_SCREEN * 4,
+            )
+        };
+    }
+    if let Some(audio_sample_batch) = callbacks.audio_sample_batch {
+        unsafe { audio_sample_batch(core.audio.as_ptr(), SAMPLES_PER_FRAME) };
+    }
+}
+
+// THE STATE HOLDS EVERY STACK SLOT, SO ITS SIZE ONLY DEPENDS ON THE MEMORY SIZE, WHICH THE
+// CORE OPTIONS DO NOT CHANGE: IT STAYS THE SAME FROM retro_load_game TO retro_unload_game
+#[no_mangle]
+pub extern "C" fn retro_serialize_size() -> usize {
+    match core().as_ref() {
+        Some(core) => core.chip8.save_state().len(),
+        None => Chip8::new().save_state().len(),
+    }
+}
+
+/// # Safety
+/// `data` must point to `size` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn retro_serialize(data: *mut c_void, size: usize) -> bool {
+    let core = core();
+    let Some(core) = core.as_ref() else {
+        return false;
+    };
+    let state = core.chip8.save_state();
+    if data.is_null() || size < state.len() {
+        return false;
+    }
+    ptr::copy_nonoverlapping(state.as_ptr(), data as *mut u8, state.len());
+    true
+}
+
+/// # Safety
+/// `data` must point to `size` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn retro_unserialize(data: *const c_void, size: usize) -> bool {
+    let mut core = core();
+    let Some(core) = core.as_mut() else {
+        return false;
+    };
+    if data.is_null() {
+        return false;
+    }
+    let state = slice::from_raw_parts(data as *const u8, size);
+    let loaded = core.chip8.load_state(state).is_ok();
+    if loaded {
+        core.halted = false;
+    }
+    loaded
+}
+
+#[no_mangle]
+pub extern "C" fn retro_cheat_reset() {}
+
+#[no_mangle]
+pub extern "C" fn retro_cheat_set(_index: c_uint, _enabled: bool, _code: *const c_char) {}
+
+/// # Safety
+/// `game` must point to a valid `retro_game_info` or be NULL.
+#[no_mangle]
+pub unsafe extern "C" fn retro_load_game(game: *const RetroGameInfo) -> bool {
+    let Some(game) = game.as_ref() else {
+        return false;
+    };
+    let rom = if !game.data.is_null() {
+        slice::from_raw_parts(game.data as *const u8, game.size).to_vec()
+    } else if !game.path.is_null() {
+        match CStr::from_ptr(game.path).to_str().ok().and_then(|path| fs::read(path).ok()) {
+            Some(rom) => rom,
+            None => return false,
+        }
+    } else {
+        return false;
+    };
+    if rom.len() > chip8_emulator::chip8::MAX_ROM_SIZE {
+        return false;
+    }
+
+    let mut pixel_format = RETRO_PIXEL_FORMAT_XRGB8888;
+    if !environment(RETRO_ENVIRONMENT_SET_PIXEL_FORMAT, &mut pixel_format as *mut _ as *mut c_void) {
+        return false;
+    }
+
+    let core = Core::new(rom);
+    *self::core() = Some(core);
+    true
+}
+
+#[no_mangle]
+pub extern "C" fn retro_load_game_special(
+    _game_type: c_uint,
+    _info: *const RetroGameInfo,
+    _num_info: usize,
+) -> bool {
+    false
+}
+
+#[no_mangle]
+pub extern "C" fn retro_unload_game() {
+    *core() = None;
+}
+
+#[no_mangle]
+pub extern "C" fn retro_get_region() -> c_uint {
+    RETRO_REGION_NTSC
+}
+
+#[no_mangle]
+pub extern "C" fn retro_get_memory_data(_id: c_uint) -> *mut c_void {
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub extern "C" fn retro_get_memory_size(_id: c_uint) -> usize {
+    0
+}
+
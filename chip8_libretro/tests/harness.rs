@@ -0,0 +1,150 @@
+// MINIMAL LIBRETRO FRONTEND: DRIVES THE CORE THROUGH ITS C ENTRY POINTS, LIKE RETROARCH WOULD
+use std::ffi::CStr;
+use std::fs;
+use std::os::raw::{c_uint, c_void};
+use std::ptr;
+use std::sync::atomic::{AtomicU16, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use chip8_libretro::libretro::*;
+use chip8_libretro::*;
+
+static FRAMES: AtomicUsize = AtomicUsize::new(0);
+static AUDIO_FRAMES: AtomicUsize = AtomicUsize::new(0);
+static PRESSED_BUTTONS: AtomicU16 = AtomicU16::new(0);
+static LAST_FRAME: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+static OPTIONS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_SET_PIXEL_FORMAT => *(data as *const i32) == RETRO_PIXEL_FORMAT_XRGB8888,
+        RETRO_ENVIRONMENT_SET_VARIABLES => {
+            // KEEP THE FIRST VALUE OF EACH OPTION, IT IS THE DEFAULT
+            let mut variable = data as *const RetroVariable;
+            let mut options = OPTIONS.lock().unwrap();
+            while !(*variable).key.is_null() {
+                let key = CStr::from_ptr((*variable).key).to_str().unwrap().to_string();
+                let value = CStr::from_ptr((*variable).value).to_str().unwrap();
+                let default = value.split("; ").nth(1).unwrap().split('|').next().unwrap();
+                options.push((key, default.to_string()));
+                variable = variable.add(1);
+            }
+            true
+        }
+        RETRO_ENVIRONMENT_GET_VARIABLE => {
+            let variable = &mut *(data as *mut RetroVariable);
+            let key = CStr::from_ptr(variable.key).to_str().unwrap();
+            let options = OPTIONS.lock().unwrap();
+            match options.iter().find(|(k, _)| k == key) {
+                Some((_, value)) => {
+                    // LEAKED ON PURPOSE, THE CORE MAY KEEP THE POINTER UNTIL THE NEXT CALL
+                    variable.value = Box::leak(format!("{}\0", value).into_boxed_str()).as_ptr() as *const _;
+                    true
+                }
+                None => false,
+            }
+        }
+        RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE => {
+            *(data as *mut bool) = false;
+            true
+        }
+        _ => false,
+    }
+}
+
+unsafe extern "C" fn video_refresh(data: *const c_void, width: c_uint, height: c_uint, pitch: usize) {
+    assert_eq!((width, height, pitch), (64, 32, 64 * 4));
+    let pixels = std::slice::from_raw_parts(data as *const u32, 64 * 32);
+    *LAST_FRAME.lock().unwrap() = pixels.to_vec();
+    FRAMES.fetch_add(1, Ordering::SeqCst);
+}
+
+unsafe extern "C" fn audio_sample_batch(_data: *const i16, frames: usize) -> usize {
+    AUDIO_FRAMES.fetch_add(frames, Ordering::SeqCst);
+    frames
+}
+
+unsafe extern "C" fn input_poll() {}
+
+unsafe extern "C" fn input_state(port: c_uint, device: c_uint, _index: c_uint, id: c_uint) -> i16 {
+    if port == 0 && device == RETRO_DEVICE_JOYPAD && id < 16 {
+        ((PRESSED_BUTTONS.load(Ordering::SeqCst) >> id) & 1) as i16
+    } else {
+        0
+    }
+}
+
+fn run_frames(count: usize) -> Vec<u32> {
+    for _ in 0..count {
+        retro_run();
+    }
+    LAST_FRAME.lock().unwrap().clone()
+}
+
+// THE CORE KEEPS GLOBAL STATE, SO EVERYTHING RUNS IN A SINGLE TEST
+#[test]
+fn core_runs_a_game_through_the_libretro_api() {
+    assert_eq!(retro_api_version(), RETRO_API_VERSION);
+    unsafe {
+        retro_set_environment(Some(environment));
+    }
+    retro_set_video_refresh(Some(video_refresh));
+    retro_set_audio_sample_batch(Some(audio_sample_batch));
+    retro_set_input_poll(Some(input_poll));
+    retro_set_input_state(Some(input_state));
+    retro_init();
+
+    let names: Vec<String> = OPTIONS.lock().unwrap().iter().map(|(k, _)| k.clone()).collect();
+    assert_eq!(names, ["chip8_quirks", "chip8_speed", "chip8_palette"]);
+
+    let mut av_info = unsafe { std::mem::zeroed::<RetroSystemAvInfo>() };
+    unsafe { retro_get_system_av_info(&mut av_info) };
+    assert_eq!(av_info.geometry.base_width, 64);
+    assert_eq!(av_info.timing.fps, 60.0);
+
+    let rom = fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/../data/BRIX")).unwrap();
+    let game = RetroGameInfo {
+        path: ptr::null(),
+        data: rom.as_ptr() as *const c_void,
+        size: rom.len(),
+        meta: ptr::null(),
+    };
+    assert!(unsafe { retro_load_game(&game) });
+
+    let frame = run_frames(60);
+    assert_eq!(FRAMES.load(Ordering::SeqCst), 60);
+    assert_eq!(AUDIO_FRAMES.load(Ordering::SeqCst), 60 * 735);
+    assert!(frame.contains(&0xFFFFFF));
+
+    // SAVE, PLAY WITH A BUTTON HELD, RESTORE AND REPLAY: THE SAME FRAME COMES OUT
+    let mut state = vec![0u8; retro_serialize_size()];
+    assert!(unsafe { retro_serialize(state.as_mut_ptr() as *mut c_void, state.len()) });
+    PRESSED_BUTTONS.store(1 << RETRO_DEVICE_ID_JOYPAD_LEFT, Ordering::SeqCst);
+    let expected = run_frames(30);
+    assert!(unsafe { retro_unserialize(state.as_ptr() as *const c_void, state.len()) });
+    assert_eq!(run_frames(30), expected);
+    assert!(!unsafe { retro_unserialize(state.as_ptr() as *const c_void, 3) });
+
+    // A STATE SAVED INSIDE TWO NESTED SUBROUTINES IS THE SIZE ANNOUNCED BEFORE THE CALLS
+    // 200: CALL 204, 204: CALL 208, 208: JUMP 208
+    let nested = [0x22, 0x04, 0x00, 0x00, 0x22, 0x08, 0x00, 0x00, 0x12, 0x08];
+    let nested_game = RetroGameInfo {
+        data: nested.as_ptr() as *const c_void,
+        size: nested.len(),
+        ..game
+    };
+    assert!(unsafe { retro_load_game(&nested_game) });
+    let size = retro_serialize_size();
+    run_frames(1);
+    assert_eq!(retro_serialize_size(), size);
+    let mut state = vec![0u8; size];
+    assert!(unsafe { retro_serialize(state.as_mut_ptr() as *mut c_void, state.len()) });
+    assert!(unsafe { retro_unserialize(state.as_ptr() as *const c_void, state.len()) });
+
+    // A NEW PALETTE IS APPLIED WHEN THE GAME IS LOADED AGAIN
+    OPTIONS.lock().unwrap()[2].1 = String::from("amber");
+    unsafe { retro_load_game(&game) };
+    assert!(run_frames(60).contains(&0xFFB000));
+
+    retro_unload_game();
+    retro_deinit();
+}
@@ -0,0 +1,5 @@
+"""Python bindings of the rust chip8 emulator."""
+
+from ._chip8 import Chip8
+
+__all__ = ["Chip8"]
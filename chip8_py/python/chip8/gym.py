@@ -0,0 +1,118 @@
+"""Gym-style environment around :class:`chip8.Chip8`.
+
+Works with ``gymnasium`` when it is installed (the environment is then a real
+``gymnasium.Env`` with action and observation spaces), and as a plain object
+with the same ``reset``/``step`` API otherwise.
+"""
+
+import os
+
+import numpy as np
+
+from ._chip8 import Chip8
+
+try:
+    import gymnasium
+    from gymnasium import spaces
+except ImportError:  # pragma: no cover - gymnasium is optional
+    gymnasium = None
+    spaces = None
+
+
+class RamValue:
+    """A number read from emulated RAM, used for rewards and episode ends.
+
+    ``length`` bytes starting at ``address`` are read big endian, or as one
+    decimal digit per byte when ``bcd`` is set (the layout FX33 writes).
+    """
+
+    def __init__(self, address, length=1, bcd=False):
+        self.address = address
+        self.length = length
+        self.bcd = bcd
+
+    def read(self, chip8):
+        value = 0
+        for byte in chip8.read_memory(self.address, self.length):
+            value = value * 10 + byte if self.bcd else (value << 8) | byte
+        return value
+
+
+def _read(source, chip8):
+    if source is None:
+        return 0
+    if callable(source):
+        return source(chip8)
+    return source.read(chip8)
+
+
+_Base = gymnasium.Env if gymnasium is not None else object
+
+
+class Chip8Env(_Base):
+    """Runs a ROM, one action being a keypad mask held for ``frames_per_step`` frames.
+
+    ``score`` and ``lives`` are :class:`RamValue` instances or callables taking the
+    :class:`Chip8`. The reward is the change of the score since the last step, and
+    the episode terminates when the lives reach 0. ``max_steps`` truncates it.
+    """
+
+    metadata = {"render_modes": ["rgb_array", "ansi"], "render_fps": 60}
+
+    def __init__(self, rom, actions=None, frames_per_step=4, score=None, lives=None,
+                 max_steps=None, seed=None, render_mode=None):
+        if isinstance(rom, (str, os.PathLike)):
+            with open(rom, "rb") as file:
+                rom = file.read()
+        self.chip8 = Chip8(bytes(rom), seed)
+        # NO KEY, THEN EACH OF THE 16 KEYS ALONE
+        self.actions = list(actions) if actions is not None else [0] + [1 << key for key in range(16)]
+        self.frames_per_step = frames_per_step
+        self.score = score
+        self.lives = lives
+        self.max_steps = max_steps
+        self.render_mode = render_mode
+        self._last_score = 0
+        self._steps = 0
+        if spaces is not None:
+            self.action_space = spaces.Discrete(len(self.actions))
+            self.observation_space = spaces.Box(0, 1, (Chip8.HEIGHT, Chip8.WIDTH), np.uint8)
+
+    def _observation(self):
+        return np.frombuffer(self.chip8.framebuffer(), np.uint8).reshape(Chip8.HEIGHT, Chip8.WIDTH).copy()
+
+    def _info(self):
+        return {"score": self._last_score, "steps": self._steps, "pc": self.chip8.pc}
+
+    def reset(self, seed=None, options=None):
+        if gymnasium is not None:
+            super().reset(seed=seed)
+        self.chip8.reset(seed)
+        self._steps = 0
+        self._last_score = _read(self.score, self.chip8)
+        return self._observation(), self._info()
+
+    def step(self, action):
+        self.chip8.step(self.actions[action], self.frames_per_step)
+        self._steps += 1
+
+        score = _read(self.score, self.chip8)
+        reward = float(score - self._last_score)
+        self._last_score = score
+
+        terminated = self.lives is not None and _read(self.lives, self.chip8) == 0
+        truncated = self.max_steps is not None and self._steps >= self.max_steps
+        return self._observation(), reward, terminated, truncated, self._info()
+
+    def render(self):
+        frame = self._observation()
+        if self.render_mode == "ansi":
+            return "\n".join("".join("#" if pixel else "." for pixel in row) for row in frame)
+        # BLACK AND WHITE RGB IMAGE
+        return np.repeat(frame[:, :, None] * 255, 3, axis=2).astype(np.uint8)
+
+    def save_state(self):
+        return self.chip8.save_state()
+
+    def load_state(self, state):
+        self.chip8.load_state(state)
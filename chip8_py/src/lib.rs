@@ -0,0 +1,197 @@
+use chip8_emulator::chip8::{MAX_ROM_SIZE, MEMORY_SIZE};
+use chip8_emulator::display::{HEIGHT_SCREEN, WIDTH_SCREEN};
+use chip8_emulator::Chip8Error;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+// PYTHON FACING WRAPPER AROUND THE CORE, EXPORTED AS `chip8.Chip8`
+#[pyclass(name = "Chip8", module = "chip8")]
+pub struct PyChip8 {
+    chip8: chip8_emulator::Chip8,
+    // KEPT TO RESTART THE SAME GAME ON reset()
+    rom: Vec<u8>,
+    seed: Option<u32>,
+}
+
+impl PyChip8 {
+    fn fresh_machine(&self) -> chip8_emulator::Chip8 {
+        let mut chip8 = chip8_emulator::Chip8::new();
+        if let Some(seed) = self.seed {
+            chip8.set_random_seed(seed);
+        }
+        chip8.load_rom(&self.rom).expect("the rom size is checked by load_rom");
+        chip8
+    }
+
+    // A FAULTING INSTRUCTION (UNKNOWN OPCODE, STACK OR MEMORY ERROR) RAISES A RuntimeError
+    fn cpu_error(error: Chip8Error) -> PyErr {
+        PyRuntimeError::new_err(error.to_string())
+    }
+
+    fn check_range(address: u16, length: usize) -> PyResult<()> {
+        if address as usize + length > MEMORY_SIZE {
+            return Err(PyValueError::new_err(format!(
+                "range {:#05X}+{} is outside of the {} bytes of memory",
+                address, length, MEMORY_SIZE
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl PyChip8 {
+    #[classattr]
+    const WIDTH: usize = WIDTH_SCREEN;
+    #[classattr]
+    const HEIGHT: usize = HEIGHT_SCREEN;
+    #[classattr]
+    const MEMORY_SIZE: usize = MEMORY_SIZE;
+
+    #[new]
+    #[pyo3(signature = (rom = None, seed = None))]
+    fn new(rom: Option<Vec<u8>>, seed: Option<u32>) -> PyResult<PyChip8> {
+        let mut chip8 = PyChip8 {
+            chip8: chip8_emulator::Chip8::new(),
+            rom: Vec::new(),
+            seed,
+        };
+        if let Some(rom) = rom {
+            chip8.load_rom(rom)?;
+        } else {
+            chip8.chip8 = chip8.fresh_machine();
+        }
+        Ok(chip8)
+    }
+
+    // STARTS THE ROM ON A FRESH MACHINE, IT IS ALSO USED BY reset()
+    fn load_rom(&mut self, rom: Vec<u8>) -> PyResult<()> {
+        if rom.len() > MAX_ROM_SIZE {
+            return Err(PyValueError::new_err(format!(
+                "rom is {} bytes, at most {} fit in memory",
+                rom.len(),
+                MAX_ROM_SIZE
+            )));
+        }
+        self.rom = rom;
+        self.chip8 = self.fresh_machine();
+        Ok(())
+    }
+
+    // RESTARTS THE LOADED ROM, OPTIONALLY WITH A NEW RANDOM SEED
+    #[pyo3(signature = (seed = None))]
+    fn reset(&mut self, seed: Option<u32>) {
+        if seed.is_some() {
+            self.seed = seed;
+        }
+        self.chip8 = self.fresh_machine();
+    }
+
+    // SETS THE KEYPAD (BIT N IS KEY N) AND RUNS `frames` FRAMES
+    #[pyo3(signature = (keys, frames = 1))]
+    fn step(&mut self, keys: u16, frames: usize) -> PyResult<()> {
+        self.chip8.change_keys_pressed(keys);
+        for _ in 0..frames {
+            self.chip8.run_frame().map_err(PyChip8::cpu_error)?;
+        }
+        Ok(())
+    }
+
+    fn run_frame(&mut self) -> PyResult<()> {
+        self.chip8.run_frame().map_err(PyChip8::cpu_error)
+    }
+
+    fn run_instruction(&mut self) -> PyResult<()> {
+        self.chip8.run_instruction().map_err(PyChip8::cpu_error)
+    }
+
+    // 64x32 BYTES, ONE PER PIXEL: numpy.frombuffer(chip8.framebuffer(), numpy.uint8).reshape(32, 64)
+    fn framebuffer<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, self.chip8.get_display())
+    }
+
+    #[pyo3(signature = (address, length = 1))]
+    fn read_memory<'py>(&self, py: Python<'py>, address: u16, length: usize) -> PyResult<Bound<'py, PyBytes>> {
+        PyChip8::check_range(address, length)?;
+        let bytes = (0..length)
+            .map(|offset| self.chip8.read_memory(address + offset as u16))
+            .collect::<Result<Vec<u8>, Chip8Error>>()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(PyBytes::new(py, &bytes))
+    }
+
+    fn write_memory(&mut self, address: u16, data: Vec<u8>) -> PyResult<()> {
+        PyChip8::check_range(address, data.len())?;
+        for (offset, byte) in data.iter().enumerate() {
+            self.chip8
+                .write_memory(address + offset as u16, *byte)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    #[getter]
+    fn registers<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, self.chip8.get_registers())
+    }
+
+    fn set_register(&mut self, x: u8, value: u8) -> PyResult<()> {
+        if x >= 16 {
+            return Err(PyValueError::new_err("register index must be between 0 and 15"));
+        }
+        self.chip8.change_register(x, value);
+        Ok(())
+    }
+
+    #[getter]
+    fn pc(&self) -> u16 {
+        self.chip8.get_pc()
+    }
+
+    #[setter]
+    fn set_pc(&mut self, pc: u16) {
+        self.chip8.change_pc(pc);
+    }
+
+    #[getter]
+    fn i(&self) -> u16 {
+        self.chip8.get_i()
+    }
+
+    #[setter]
+    fn set_i(&mut self, i: u16) {
+        self.chip8.change_i(i);
+    }
+
+    #[getter]
+    fn delay_timer(&self) -> u8 {
+        self.chip8.get_delay_timer()
+    }
+
+    #[getter]
+    fn sound_timer(&self) -> u8 {
+        self.chip8.get_sound_timer()
+    }
+
+    #[getter]
+    fn sound_active(&self) -> bool {
+        self.chip8.is_sound_active()
+    }
+
+    fn save_state<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.chip8.save_state())
+    }
+
+    fn load_state(&mut self, state: Vec<u8>) -> PyResult<()> {
+        self.chip8
+            .load_state(&state)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[pymodule]
+fn _chip8(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyChip8>()?;
+    Ok(())
+}
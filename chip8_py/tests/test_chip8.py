@@ -0,0 +1,90 @@
+"""Run with: maturin develop && python -m unittest discover chip8_py/tests"""
+
+import pathlib
+import unittest
+
+from chip8 import Chip8
+
+DATA = pathlib.Path(__file__).resolve().parents[2] / "data"
+
+
+def rom(name):
+    return (DATA / name).read_bytes()
+
+
+class Chip8Test(unittest.TestCase):
+    def test_framebuffer_size(self):
+        chip8 = Chip8()
+        self.assertEqual(len(chip8.framebuffer()), Chip8.WIDTH * Chip8.HEIGHT)
+
+    def test_step_runs_the_rom(self):
+        chip8 = Chip8(rom("BRIX"), seed=1)
+        chip8.step(0, frames=60)
+        self.assertIn(1, chip8.framebuffer())
+
+    def test_registers_and_memory(self):
+        chip8 = Chip8(bytes([0x60, 0x2A, 0xA3, 0x00]))
+        chip8.run_instruction()
+        chip8.run_instruction()
+        self.assertEqual(chip8.registers[0], 0x2A)
+        self.assertEqual(chip8.i, 0x300)
+        chip8.write_memory(0x300, b"\x01\x02")
+        self.assertEqual(chip8.read_memory(0x300, 2), b"\x01\x02")
+        with self.assertRaises(ValueError):
+            chip8.read_memory(0xFFF, 2)
+
+    def test_reset_replays_the_same_game(self):
+        chip8 = Chip8(rom("PONG"), seed=7)
+        chip8.step(0, frames=120)
+        first = chip8.framebuffer()
+        chip8.reset()
+        chip8.step(0, frames=120)
+        self.assertEqual(chip8.framebuffer(), first)
+
+    def test_save_and_load_state(self):
+        chip8 = Chip8(rom("PONG"))
+        chip8.step(0, frames=30)
+        state = chip8.save_state()
+        chip8.step(1 << 1, frames=30)
+        expected = chip8.framebuffer()
+        chip8.load_state(state)
+        chip8.step(1 << 1, frames=30)
+        self.assertEqual(chip8.framebuffer(), expected)
+        with self.assertRaises(ValueError):
+            chip8.load_state(b"nope")
+
+
+try:
+    import numpy  # noqa: F401
+except ImportError:
+    numpy = None
+
+
+@unittest.skipIf(numpy is None, "numpy is not installed")
+class Chip8EnvTest(unittest.TestCase):
+    def test_reward_comes_from_ram(self):
+        from chip8.gym import Chip8Env, RamValue
+
+        env = Chip8Env(DATA / "BRIX", score=RamValue(0x300), max_steps=3)
+        observation, _ = env.reset(seed=3)
+        self.assertEqual(observation.shape, (Chip8.HEIGHT, Chip8.WIDTH))
+        env.chip8.write_memory(0x300, b"\x05")
+        _, reward, terminated, truncated, _ = env.step(0)
+        self.assertEqual(reward, 5.0)
+        self.assertFalse(terminated)
+        self.assertFalse(truncated)
+        env.step(0)
+        *_, truncated, _ = env.step(0)
+        self.assertTrue(truncated)
+
+    def test_bcd_value(self):
+        from chip8.gym import RamValue
+
+        chip8 = Chip8()
+        chip8.write_memory(0x300, bytes([1, 2, 3]))
+        self.assertEqual(RamValue(0x300, 3, bcd=True).read(chip8), 123)
+        self.assertEqual(RamValue(0x300, 2).read(chip8), 0x0102)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,80 @@
+use chip8_emulator::display::{HEIGHT_SCREEN, WIDTH_SCREEN};
+use wasm_bindgen::prelude::*;
+
+// JS FACING WRAPPER AROUND THE CORE, EXPORTED AS `Chip8`
+#[wasm_bindgen(js_name = Chip8)]
+pub struct Chip8 {
+    chip8: chip8_emulator::Chip8,
+    // KEPT FOR THE FRESH MACHINE load_rom STARTS
+    seed: Option<u32>,
+}
+
+impl Default for Chip8 {
+    fn default() -> Chip8 {
+        Chip8::new()
+    }
+}
+
+#[wasm_bindgen(js_class = Chip8)]
+impl Chip8 {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Chip8 {
+        Chip8 {
+            chip8: chip8_emulator::Chip8::new(),
+            seed: None,
+        }
+    }
+
+    // STARTS THE ROM ON A FRESH MACHINE, SEEDED WITH THE LAST set_random_seed IF ANY
+    pub fn load_rom(&mut self, rom: &[u8]) -> Result<(), JsError> {
+        self.chip8 = chip8_emulator::Chip8::new();
+        if let Some(seed) = self.seed {
+            self.chip8.set_random_seed(seed);
+        }
+        self.chip8.load_rom(rom).map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    pub fn set_random_seed(&mut self, seed: u32) {
+        self.seed = Some(seed);
+        self.chip8.set_random_seed(seed);
+    }
+
+    // THROWS WHEN THE PROGRAM FAULTS (UNKNOWN OPCODE, STACK OR MEMORY ERROR)
+    pub fn run_frame(&mut self) -> Result<(), JsError> {
+        self.chip8.run_frame().map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    // BIT N OF THE MASK IS KEY N OF THE KEYPAD
+    pub fn set_keys(&mut self, mask: u16) {
+        self.chip8.change_keys_pressed(mask);
+    }
+
+    pub fn keys(&self) -> u16 {
+        self.chip8.get_keys_pressed()
+    }
+
+    // ONE BYTE PER PIXEL (0 OR 1), ROW BY ROW, RETURNED AS A Uint8Array
+    pub fn framebuffer(&self) -> Vec<u8> {
+        self.chip8.get_display().to_vec()
+    }
+
+    pub fn width() -> usize {
+        WIDTH_SCREEN
+    }
+
+    pub fn height() -> usize {
+        HEIGHT_SCREEN
+    }
+
+    pub fn sound_active(&self) -> bool {
+        self.chip8.is_sound_active()
+    }
+
+    pub fn save_state(&self) -> Vec<u8> {
+        self.chip8.save_state()
+    }
+
+    pub fn load_state(&mut self, state: &[u8]) -> Result<(), JsError> {
+        self.chip8.load_state(state).map_err(|e| JsError::new(&e.to_string()))
+    }
+}
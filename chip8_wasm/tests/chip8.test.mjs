@@ -0,0 +1,94 @@
+// RUN WITH:
+//   wasm-pack build chip8_wasm --target nodejs
+//   node --test chip8_wasm/tests/
+import { test } from 'node:test';
+import assert from 'node:assert/strict';
+import { readFileSync } from 'node:fs';
+import { fileURLToPath } from 'node:url';
+import { Chip8 } from '../pkg/chip8_wasm.js';
+
+const rom = (name) => readFileSync(fileURLToPath(new URL(`../../data/${name}`, import.meta.url)));
+
+test('framebuffer has one byte per pixel', () => {
+  const chip8 = new Chip8();
+  const framebuffer = chip8.framebuffer();
+  assert.ok(framebuffer instanceof Uint8Array);
+  assert.equal(framebuffer.length, Chip8.width() * Chip8.height());
+  assert.ok(framebuffer.every((pixel) => pixel === 0));
+});
+
+test('running a rom draws something', () => {
+  const chip8 = new Chip8();
+  chip8.load_rom(rom('BRIX'));
+  for (let frame = 0; frame < 60; frame++) {
+    chip8.run_frame();
+  }
+  assert.ok(chip8.framebuffer().some((pixel) => pixel === 1));
+});
+
+test('save state restores the same frames', () => {
+  const chip8 = new Chip8();
+  chip8.load_rom(rom('PONG'));
+  for (let frame = 0; frame < 30; frame++) {
+    chip8.run_frame();
+  }
+  const state = chip8.save_state();
+  for (let frame = 0; frame < 30; frame++) {
+    chip8.run_frame();
+  }
+  const expected = chip8.framebuffer();
+
+  const restored = new Chip8();
+  restored.load_state(state);
+  for (let frame = 0; frame < 30; frame++) {
+    restored.run_frame();
+  }
+  assert.deepEqual(restored.framebuffer(), expected);
+});
+
+test('keys and sound are exposed', () => {
+  const chip8 = new Chip8();
+  chip8.set_keys(0b1000_0000_0000_0001);
+  assert.equal(chip8.keys(), 0b1000_0000_0000_0001);
+  chip8.set_keys(0);
+  assert.equal(chip8.keys(), 0);
+
+  // 200: V1 = 0, V0 = KEY, I = FONT OF V0, DRAW IT AT (0, 0), SOUND TIMER = V0, JUMP 20A
+  chip8.load_rom(new Uint8Array([0x61, 0x00, 0xf0, 0x0a, 0xf0, 0x29, 0xd1, 0x15, 0xf0, 0x18, 0x12, 0x0a]));
+  for (let frame = 0; frame < 3; frame++) {
+    chip8.run_frame();
+  }
+  assert.ok(chip8.framebuffer().every((pixel) => pixel === 0));
+  assert.equal(chip8.sound_active(), false);
+
+  // KEY F: ITS GLYPH STARTS WITH ROWS 0xF0 AND 0x80, AND THE SOUND TIMER GETS 15
+  chip8.set_keys(1 << 0xf);
+  chip8.run_frame();
+  const framebuffer = chip8.framebuffer();
+  assert.deepEqual([...framebuffer.slice(0, 5)], [1, 1, 1, 1, 0]);
+  assert.deepEqual([...framebuffer.slice(Chip8.width(), Chip8.width() + 2)], [1, 0]);
+  assert.equal(chip8.sound_active(), true);
+});
+
+test('loading garbage as a state throws', () => {
+  const chip8 = new Chip8();
+  assert.throws(() => chip8.load_state(new Uint8Array([1, 2, 3])));
+});
+
+test('the random seed survives loading a rom', () => {
+  // 200: V0 = RANDOM, JUMP 202
+  const cxnn = new Uint8Array([0xc0, 0xff, 0x12, 0x02]);
+  const run = (seedFirst) => {
+    const chip8 = new Chip8();
+    if (seedFirst) {
+      chip8.set_random_seed(1234);
+    }
+    chip8.load_rom(cxnn);
+    if (!seedFirst) {
+      chip8.set_random_seed(1234);
+    }
+    chip8.run_frame();
+    return chip8.save_state();
+  };
+  assert.deepEqual(run(true), run(false));
+});
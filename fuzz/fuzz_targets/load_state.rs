@@ -0,0 +1,23 @@
+#![no_main]
+
+use chip8_emulator::Chip8;
+use libfuzzer_sys::fuzz_target;
+
+// A SAVE STATE COMES FROM A FILE, ANY BYTES HAVE TO BE REJECTED CLEANLY
+fuzz_target!(|data: &[u8]| {
+    let mut chip8 = Chip8::new();
+    let before = chip8.save_state();
+    match chip8.load_state(data) {
+        // AN ACCEPTED STATE SAVES BACK TO THE SAME BYTES AND CAN BE RUN
+        Ok(()) => {
+            assert_eq!(chip8.save_state(), data);
+            for _ in 0..10 {
+                if chip8.run_frame().is_err() {
+                    break;
+                }
+            }
+        }
+        // A REJECTED ONE LEAVES THE MACHINE UNTOUCHED
+        Err(_) => assert_eq!(chip8.save_state(), before),
+    }
+});
@@ -0,0 +1,62 @@
+#![no_main]
+
+use chip8_emulator::chip8::MAX_ROM_SIZE;
+use chip8_emulator::config::MAX_STACK_DEPTH;
+use chip8_emulator::{Addressing, Chip8, Config, Quirks, Stack, Timing};
+use libfuzzer_sys::fuzz_target;
+
+// FRAMES RUN FOR EACH INPUT
+const FRAMES: usize = 200;
+
+// THE FIRST 4 BYTES PICK THE CONFIG AND THE KEY HELD DOWN, THE REST IS THE ROM:
+// 0: ONE BIT PER QUIRK, THEN Timing::Vip AND skip_idle_loops
+// 1: ADDRESSING IN THE LOW 2 BITS, THEN THE STACK IN RAM, BIT 3 UNUSED AND THE KEY IN THE HIGH
+//    NIBBLE
+// 2: STACK DEPTH IN THE LOW 7 BITS, 0 TO MAX_STACK_DEPTH, THEN decode_cache
+// 3: INSTRUCTIONS PER FRAME MINUS 1
+// THE CORE MUST NEVER PANIC, A BROKEN ROM ONLY STOPS WITH AN ERROR.
+fuzz_target!(|data: &[u8]| {
+    let Some((header, rom)) = data.split_first_chunk::<4>() else {
+        return;
+    };
+    let bit = |byte: u8, n: u8| byte & (1 << n) != 0;
+    let config = Config {
+        quirks: Quirks {
+            vf_reset: bit(header[0], 0),
+            memory_increments_i: bit(header[0], 1),
+            shift_uses_vy: bit(header[0], 2),
+            jump_uses_vx: bit(header[0], 3),
+            clip_sprites: bit(header[0], 4),
+            fx1e_sets_vf: bit(header[0], 5),
+        },
+        timing: if bit(header[0], 6) { Timing::Vip } else { Timing::Fixed },
+        skip_idle_loops: bit(header[0], 7),
+        addressing: match header[1] & 0x3 {
+            0 => Addressing::Wrap12,
+            1 => Addressing::Wrap16,
+            _ => Addressing::Trap,
+        },
+        stack: Stack {
+            depth: (header[2] & 0x7F) as usize % (MAX_STACK_DEPTH + 1),
+            in_ram: bit(header[1], 2),
+        },
+        decode_cache: bit(header[2], 7),
+        instructions_per_frame: header[3] as usize + 1,
+    };
+    let mut chip8 = Chip8::with_config(config);
+    if chip8.load_rom(rom).is_err() {
+        assert!(rom.len() > MAX_ROM_SIZE);
+        return;
+    }
+    chip8.change_keys_pressed(1 << (header[1] >> 4));
+    for _ in 0..FRAMES {
+        if chip8.run_frame().is_err() {
+            break;
+        }
+    }
+
+    // WHATEVER THE PROGRAM DID, ITS STATE SAVES AND LOADS BACK
+    let state = chip8.save_state();
+    chip8.load_state(&state).unwrap();
+    assert_eq!(chip8.save_state(), state);
+});
@@ -0,0 +1,32 @@
+use std::env;
+use std::fs;
+use std::process;
+use chip8_emulator::control_flow::ControlFlowGraph;
+
+// PRINTS THE CONTROL-FLOW GRAPH OF A ROM AS GRAPHVIZ DOT, OR AS JSON WITH --json, e.g.
+// chip8_cfg data/PONG | dot -Tsvg > pong.svg
+// THE SELF-MODIFYING CODE WARNINGS ALSO GO TO STDERR.
+fn main() {
+    let mut json = false;
+    let mut path = None;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ => path = Some(arg),
+        }
+    }
+    let Some(path) = path else {
+        eprintln!("usage: chip8_cfg ROM [--json]");
+        process::exit(2);
+    };
+    let rom = fs::read(&path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        process::exit(1);
+    });
+
+    let graph = ControlFlowGraph::build(&rom);
+    for warning in &graph.warnings {
+        eprintln!("{:04X}: {}", warning.address, warning.message);
+    }
+    print!("{}", if json { graph.to_json() } else { graph.to_dot() });
+}
@@ -0,0 +1,17 @@
+use std::env;
+use std::fs;
+use std::process;
+use chip8_emulator::decompiler;
+
+// PRINTS A ROM AS OCTO SOURCE, e.g. chip8_decompile data/PONG > pong.8o
+fn main() {
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("usage: chip8_decompile ROM");
+        process::exit(2);
+    };
+    let rom = fs::read(&path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        process::exit(1);
+    });
+    print!("{}", decompiler::decompile(&rom));
+}
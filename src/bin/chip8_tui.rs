@@ -0,0 +1,255 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Stdout, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+use chip8_emulator::chip8::Chip8;
+use chip8_emulator::display::{self, HEIGHT_SCREEN, WIDTH_SCREEN};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+// TERMINALS DO NOT REPORT KEY RELEASES, SO A KEY STAYS PRESSED FOR A FEW FRAMES
+const KEY_HOLD_FRAMES: u32 = 6;
+
+// COLUMN WHERE THE SIDEBAR STARTS, RIGHT OF THE SCREEN AND ITS BORDER
+const SIDEBAR_COLUMN: u16 = WIDTH_SCREEN as u16 + 4;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    // 1x2 PIXELS PER CHARACTER
+    HalfBlock,
+    // 2x4 PIXELS PER CHARACTER
+    Braille,
+}
+
+// PUTS THE TERMINAL BACK IN ITS ORIGINAL STATE, EVEN WHEN THE EMULATOR PANICS
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn new(stdout: &mut Stdout) -> io::Result<TerminalGuard> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout, EnterAlternateScreen, Hide, Clear(ClearType::All))?;
+        Ok(TerminalGuard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut rom_path = String::from("data/TICTAC");
+    let mut mode = Mode::HalfBlock;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--braille" => mode = Mode::Braille,
+            "--half-block" => mode = Mode::HalfBlock,
+            _ => rom_path = arg,
+        }
+    }
+
+    // LOAD THE ROM
+    let mut data = Vec::<u8>::new();
+    File::open(&rom_path)?.read_to_end(&mut data)?;
+
+    let mut chip8 = Chip8::new();
+    chip8.load_rom(&data).map_err(io::Error::other)?;
+
+    let mut stdout = io::stdout();
+    let guard = TerminalGuard::new(&mut stdout)?;
+
+    let frame_duration = Duration::from_micros(16_667);
+    let mut next_frame = Instant::now();
+    let mut held_key: Option<(u8, u32)> = None;
+    let mut first_frame = true;
+
+    draw_border(&mut stdout, mode, &rom_path)?;
+
+    'running: loop {
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key_event) = event::read()? {
+                if key_event.kind == KeyEventKind::Release {
+                    continue;
+                }
+                // RAW MODE TURNS CTRL-C INTO A PLAIN KEY PRESS INSTEAD OF AN INTERRUPT
+                let interrupt = key_event.code == KeyCode::Char('c') && key_event.modifiers.contains(KeyModifiers::CONTROL);
+                if key_event.code == KeyCode::Esc || interrupt {
+                    break 'running;
+                }
+                if let Some(key) = get_keycode_by_key(key_event.code) {
+                    held_key = Some((key, KEY_HOLD_FRAMES));
+                    chip8.change_key_pressed(Some(key));
+                }
+            }
+        }
+
+        if let Err(error) = chip8.run_frame() {
+            // LEAVE THE ALTERNATE SCREEN FIRST SO THE ERROR STAYS VISIBLE
+            drop(guard);
+            return Err(io::Error::other(error));
+        }
+
+        // RELEASE THE KEY ONCE IT HAS NOT BEEN REPEATED FOR A WHILE
+        held_key = match held_key {
+            Some((_, 0)) => {
+                chip8.change_key_pressed(None);
+                None
+            }
+            Some((key, frames)) => Some((key, frames - 1)),
+            None => None,
+        };
+
+        if chip8.is_display_dirty() || first_frame {
+            match mode {
+                Mode::HalfBlock => draw_half_blocks(&mut stdout, chip8.get_display())?,
+                Mode::Braille => draw_braille(&mut stdout, chip8.get_display())?,
+            }
+            chip8.clear_display_dirty();
+            first_frame = false;
+        }
+        draw_sidebar(&mut stdout, &chip8, held_key.map(|(key, _)| key))?;
+        stdout.flush()?;
+
+        next_frame += frame_duration;
+        let now = Instant::now();
+        if next_frame > now {
+            thread::sleep(next_frame - now);
+        } else {
+            next_frame = now;
+        }
+    }
+
+    Ok(())
+}
+
+fn pixel(screen: &[u8], x: usize, y: usize) -> bool {
+    x < WIDTH_SCREEN && y < HEIGHT_SCREEN
+        && screen[display::Display::get_position_from_coords(x, y)] == 1
+}
+
+fn draw_border(stdout: &mut Stdout, mode: Mode, rom_path: &str) -> io::Result<()> {
+    let (columns, rows) = match mode {
+        Mode::HalfBlock => (WIDTH_SCREEN, HEIGHT_SCREEN / 2),
+        Mode::Braille => (WIDTH_SCREEN / 2, HEIGHT_SCREEN / 4),
+    };
+    let horizontal = "─".repeat(columns);
+    queue!(stdout, MoveTo(0, 0), Print(format!("┌{}┐", horizontal)))?;
+    for row in 0..rows {
+        let y = row as u16 + 1;
+        queue!(stdout, MoveTo(0, y), Print("│"), MoveTo(columns as u16 + 1, y), Print("│"))?;
+    }
+    queue!(stdout, MoveTo(0, rows as u16 + 1), Print(format!("└{}┘", horizontal)))?;
+    queue!(stdout, MoveTo(0, rows as u16 + 2), Print(format!("{} - ESC to exit", rom_path)))?;
+    Ok(())
+}
+
+fn draw_half_blocks(stdout: &mut Stdout, screen: &[u8]) -> io::Result<()> {
+    for row in 0..HEIGHT_SCREEN / 2 {
+        let mut line = String::with_capacity(WIDTH_SCREEN * 3);
+        for x in 0..WIDTH_SCREEN {
+            let top = pixel(screen, x, row * 2);
+            let bottom = pixel(screen, x, row * 2 + 1);
+            line.push(match (top, bottom) {
+                (false, false) => ' ',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (true, true) => '█',
+            });
+        }
+        queue!(stdout, MoveTo(1, row as u16 + 1), Print(line))?;
+    }
+    Ok(())
+}
+
+fn draw_braille(stdout: &mut Stdout, screen: &[u8]) -> io::Result<()> {
+    // BIT OF EACH DOT IN A BRAILLE CELL, INDEXED BY [ROW][COLUMN]
+    const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+    for row in 0..HEIGHT_SCREEN / 4 {
+        let mut line = String::with_capacity(WIDTH_SCREEN / 2 * 3);
+        for column in 0..WIDTH_SCREEN / 2 {
+            let mut cell = 0;
+            for (dy, dots) in DOTS.iter().enumerate() {
+                for (dx, dot) in dots.iter().enumerate() {
+                    if pixel(screen, column * 2 + dx, row * 4 + dy) {
+                        cell |= dot;
+                    }
+                }
+            }
+            line.push(char::from_u32(0x2800 + cell).unwrap_or(' '));
+        }
+        queue!(stdout, MoveTo(1, row as u16 + 1), Print(line))?;
+    }
+    Ok(())
+}
+
+fn draw_sidebar(stdout: &mut Stdout, chip8: &Chip8, key: Option<u8>) -> io::Result<()> {
+    let registers = chip8.get_registers();
+    for row in 0..8 {
+        let text = format!(
+            "V{:X} {:02X}   V{:X} {:02X}",
+            row, registers[row], row + 8, registers[row + 8]
+        );
+        queue!(stdout, MoveTo(SIDEBAR_COLUMN, row as u16 + 1), Print(text))?;
+    }
+
+    let lines = [
+        format!("PC {:03X}   I  {:03X}", chip8.get_pc(), chip8.get_i()),
+        format!("DT {:02X}    ST {:02X}", chip8.get_delay_timer(), chip8.get_sound_timer()),
+        match chip8.get_current_opcode() {
+            Ok(opcode) => format!("OP {:04X}", opcode),
+            Err(_) => String::from("OP ----"),
+        },
+        match key {
+            Some(key) => format!("KEY {:X}", key),
+            None => String::from("KEY -"),
+        },
+    ];
+    for (row, line) in lines.iter().enumerate() {
+        queue!(stdout, MoveTo(SIDEBAR_COLUMN, row as u16 + 10), Print(format!("{:<16}", line)))?;
+    }
+    Ok(())
+}
+
+fn get_keycode_by_key(code: KeyCode) -> Option<u8> {
+    let c = match code {
+        KeyCode::Char(c) => c.to_ascii_lowercase(),
+        _ => return None,
+    };
+    match c {
+        // ORIGINAL : 1 2 3 C
+        // EMULATOR : A Z E R
+        'a' => Some(0x1),
+        'z' => Some(0x2),
+        'e' => Some(0x3),
+        'r' => Some(0xC),
+
+        // ORIGINAL : 4 5 6 D
+        // EMULATOR : Q S D F
+        'q' => Some(0x4),
+        's' => Some(0x5),
+        'd' => Some(0x6),
+        'f' => Some(0xD),
+
+        // ORIGINAL : 7 8 9 E
+        // EMULATOR : W X C V
+        'w' => Some(0x7),
+        'x' => Some(0x8),
+        'c' => Some(0x9),
+        'v' => Some(0xE),
+
+        // ORIGINAL : A 0 B F
+        // EMULATOR : U I O P
+        'u' => Some(0xA),
+        'i' => Some(0x0),
+        'o' => Some(0xB),
+        'p' => Some(0xF),
+        _ => None,
+    }
+}
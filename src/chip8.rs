@@ -1,40 +1,373 @@
+use crate::config::{Config, Timing};
 use crate::cpu::Cpu;
 use crate::cpu;
 use crate::connector::Connector;
+use crate::coverage::Coverage;
+use crate::error::Chip8Error;
+use crate::idle::IdleLoop;
+use crate::machine::MachineBuilder;
+use crate::profiler::Profiler;
+use crate::state::{StateError, StateReader, StateWriter};
+use crate::timing::{self, Executed};
+
+pub use crate::ram::MEMORY_SIZE;
+// WHERE load_rom PUTS THE ROM AND EXECUTION STARTS
+pub const PROGRAM_START: u16 = cpu::START_ADDRESS;
+// WITH THE DEFAULT 4 KB OF MEMORY
+pub const MAX_ROM_SIZE: usize = MEMORY_SIZE - cpu::START_ADDRESS as usize;
+
+// 10 INSTRUCTIONS PER 60 HZ FRAME GIVES ROUGHLY 600 INSTRUCTIONS PER SECOND
+pub const INSTRUCTIONS_PER_FRAME: usize = 10;
 
 pub struct Chip8 {
     connector: Connector,
-    cpu: Cpu
+    cpu: Cpu,
+    config: Config,
+    // CYCLES THE LAST INSTRUCTION OF THE PREVIOUS FRAME OVERRAN BY, WITH VIP TIMING
+    cycles_overrun: u32,
+    // INSTRUCTIONS RUN SO FAR, THE ITERATIONS OF SKIPPED IDLE LOOPS INCLUDED
+    instructions_run: u64,
+    // ONLY PAID FOR WHILE PROFILING OR TRACKING COVERAGE
+    profiler: Option<Profiler>,
+    coverage: Option<Coverage>,
+}
+
+// NAME USED BY THE TEST HELPERS: Machine::builder()...build()
+pub type Machine = Chip8;
+
+// A SUBROUTINE CALL THAT HAS NOT RETURNED YET
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallFrame {
+    // ADDRESS OF THE 2NNN INSTRUCTION THAT MADE THE CALL
+    pub caller: u16,
+    // WHERE 00EE WILL JUMP BACK TO
+    pub return_address: u16,
+}
+
+impl Default for Chip8 {
+    fn default() -> Chip8 {
+        Chip8::new()
+    }
 }
 
 impl Chip8 {
     pub fn new() -> Chip8 {
+        Chip8::with_config(Config::default())
+    }
+
+    pub fn with_config(config: Config) -> Chip8 {
+        let mut connector = Connector::new(config.addressing.memory_size());
+        connector.change_decode_cache(config.decode_cache);
         Chip8 {
-            connector: Connector::new(),
-            cpu: Cpu::new()
+            connector,
+            cpu: Cpu::new(config.quirks, config.addressing, config.stack),
+            config,
+            cycles_overrun: 0,
+            instructions_run: 0,
+            profiler: None,
+            coverage: None,
         }
     }
 
-    pub fn load_rom(&mut self, data: &Vec<u8>) {
-        for value in 0..data.len() {
-            self.connector.write_byte_ram(cpu::START_ADDRESS + value as u16, data[value]);
+    pub fn builder() -> MachineBuilder {
+        MachineBuilder::new()
+    }
+
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+
+    // CAN BE CALLED WHILE A GAME IS RUNNING, THE NEXT INSTRUCTION USES THE NEW CONFIG.
+    // GOING FROM 64 KB TO 4 KB OF MEMORY DROPS EVERYTHING PAST 0xFFF.
+    pub fn change_config(&mut self, config: Config) {
+        self.cpu.change_quirks(config.quirks);
+        self.cpu.change_addressing(config.addressing);
+        self.connector.change_memory_size(config.addressing.memory_size());
+        self.connector.change_decode_cache(config.decode_cache);
+        self.cpu.change_stack_config(&mut self.connector, config.stack);
+        self.config = config;
+    }
+
+    pub fn load_rom(&mut self, data: &[u8]) -> Result<(), Chip8Error> {
+        if data.len() > self.connector.get_memory_size() - cpu::START_ADDRESS as usize {
+            return Err(Chip8Error::RomTooLarge(data.len()));
+        }
+        for (offset, value) in data.iter().enumerate() {
+            self.connector.write_byte_ram(cpu::START_ADDRESS + offset as u16, *value)?;
+        }
+        Ok(())
+    }
+
+    // AN INSTRUCTION THAT FAILS IS NOT COUNTED AS RUN
+    pub fn run_instruction(&mut self) -> Result<(), Chip8Error> {
+        if self.profiler.is_none() && self.coverage.is_none() {
+            self.cpu.run_instruction(&mut self.connector)?;
+            self.instructions_run += 1;
+            return Ok(());
+        }
+        let pc = self.cpu.get_pc();
+        let i = self.cpu.get_i();
+        let opcode = self.cpu.peek_opcode(&self.connector)?;
+        if let Some(profiler) = self.profiler.as_mut() {
+            profiler.record(pc, opcode, self.cpu.get_stack(), self.connector.get_memory());
+        }
+        self.cpu.run_instruction(&mut self.connector)?;
+        self.instructions_run += 1;
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.record(pc, opcode, i, self.cpu.get_pc());
         }
+        Ok(())
     }
 
-    pub fn run_instruction(&mut self) {
+    // STARTS PROFILING THE INSTRUCTIONS RUN FROM NOW ON, DROPPING ANY PREVIOUS PROFILE
+    pub fn start_profiler(&mut self) {
+        self.profiler = Some(Profiler::new());
+    }
+
+    pub fn stop_profiler(&mut self) -> Option<Profiler> {
+        self.profiler.take()
+    }
+
+    pub fn get_profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    // STARTS TRACKING COVERAGE FROM NOW ON, DROPPING ANY PREVIOUS COVERAGE
+    pub fn start_coverage(&mut self) {
+        self.coverage = Some(Coverage::new(self.connector.get_memory_size()));
+    }
+
+    pub fn stop_coverage(&mut self) -> Option<Coverage> {
+        self.coverage.take()
+    }
+
+    pub fn get_coverage(&self) -> Option<&Coverage> {
+        self.coverage.as_ref()
+    }
+
+    // RUNS ONE 60 HZ FRAME: THE INSTRUCTIONS THE TIMING MODE ALLOWS, THEN ONE TIMER TICK.
+    // STOPS AT THE FIRST ERROR WITHOUT TICKING THE TIMERS.
+    pub fn run_frame(&mut self) -> Result<(), Chip8Error> {
+        match self.config.timing {
+            Timing::Fixed => {
+                let mut remaining = self.config.instructions_per_frame;
+                while remaining > 0 {
+                    if let Some(idle) = self.idle_loop() {
+                        let skipped = remaining / idle.instructions * idle.instructions;
+                        if skipped > 0 {
+                            idle.skip(&mut self.cpu, &self.connector);
+                            self.instructions_run += skipped as u64;
+                            remaining -= skipped;
+                            continue;
+                        }
+                    }
+                    self.run_instruction()?;
+                    remaining -= 1;
+                }
+            },
+            Timing::Vip => self.run_vip_frame()?,
+        }
         self.connector.tick();
-        self.cpu.run_instruction(&mut self.connector);
+        Ok(())
+    }
+
+    // WHAT A FRAME RUNS DEPENDS ON THE TIMING MODE, THIS COUNTS WHAT ACTUALLY RAN
+    pub fn get_instructions_run(&self) -> u64 {
+        self.instructions_run
+    }
+
+    // A LONG INSTRUCTION CAN END PAST THE FRAME, THE NEXT FRAME STARTS THAT MUCH LATER
+    fn run_vip_frame(&mut self) -> Result<(), Chip8Error> {
+        let mut cycles = self.cycles_overrun;
+        let mut first = true;
+        while cycles < timing::VIP_CYCLES_PER_FRAME {
+            // ONLY THE ITERATIONS ENDING INSIDE THE FRAME ARE SKIPPED, SO THE OVERRUN IS THE SAME
+            if let Some(idle) = self.idle_loop() {
+                let iterations = (timing::VIP_CYCLES_PER_FRAME - cycles) / idle.cycles;
+                if iterations > 0 {
+                    idle.skip(&mut self.cpu, &self.connector);
+                    self.instructions_run += (iterations as usize * idle.instructions) as u64;
+                    cycles += iterations * idle.cycles;
+                    first = false;
+                    continue;
+                }
+            }
+            let opcode = self.get_current_opcode()?;
+            // DXYN WAITS FOR THE DISPLAY INTERRUPT, SO IT RUNS FIRST THING NEXT FRAME
+            if opcode & 0xF000 == 0xD000 && !first {
+                cycles = timing::VIP_CYCLES_PER_FRAME;
+                break;
+            }
+            let pc = self.cpu.get_pc();
+            let registers = *self.cpu.get_registers();
+            self.run_instruction()?;
+            cycles += timing::vip_cycles(&Executed {
+                opcode,
+                vx: registers[((opcode & 0x0F00) >> 8) as usize],
+                v0: registers[0],
+                // PC MOVED 4 BYTES, COUNTED ROUND THE END OF MEMORY WHEN THE SKIP WRAPPED
+                skipped: (self.cpu.get_pc() as usize).wrapping_sub(pc as usize) % self.connector.get_memory_size() == 4,
+            });
+            first = false;
+        }
+        self.cycles_overrun = cycles - timing::VIP_CYCLES_PER_FRAME;
+        Ok(())
+    }
+
+    // THE PROFILER AND COVERAGE SEE EVERY INSTRUCTION, NOTHING IS SKIPPED WHILE THEY RUN
+    fn idle_loop(&self) -> Option<IdleLoop> {
+        if !self.config.skip_idle_loops || self.profiler.is_some() || self.coverage.is_some() {
+            return None;
+        }
+        IdleLoop::detect(&self.cpu, &self.connector)
     }
 
     pub fn get_display(&self) -> &[u8] {
         self.connector.get_display()
     }
 
+    pub fn is_display_dirty(&self) -> bool {
+        self.connector.is_display_dirty()
+    }
+
+    pub fn is_display_row_dirty(&self, y: usize) -> bool {
+        self.connector.is_display_row_dirty(y)
+    }
+
+    pub fn clear_display_dirty(&mut self) {
+        self.connector.clear_display_dirty();
+    }
+
     pub fn change_key_pressed(&mut self, key: Option<u8>) {
         self.connector.change_key_pressed(key);
     }
 
+    // SETS THE WHOLE KEYPAD AT ONCE, BIT N BEING KEY N
+    pub fn change_keys_pressed(&mut self, mask: u16) {
+        self.connector.change_keys_pressed(mask);
+    }
+
+    pub fn get_keys_pressed(&self) -> u16 {
+        self.connector.get_keys_pressed()
+    }
+
+    pub fn is_sound_active(&self) -> bool {
+        self.connector.is_sound_active()
+    }
+
+    pub fn set_random_seed(&mut self, seed: u32) {
+        self.cpu.set_random_seed(seed);
+    }
+
+    pub fn save_state(&self) -> Vec<u8> {
+        let mut writer = StateWriter::new();
+        self.cpu.save_state(&mut writer);
+        self.connector.save_state(&mut writer);
+        writer.into_bytes()
+    }
+
+    // THE MACHINE IS LEFT UNTOUCHED WHEN THE STATE CANNOT BE LOADED
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), StateError> {
+        let mut reader = StateReader::new(data)?;
+        let mut cpu = Cpu::new(self.config.quirks, self.config.addressing, self.config.stack);
+        let mut connector = Connector::new(self.config.addressing.memory_size());
+        connector.change_decode_cache(self.config.decode_cache);
+        cpu.load_state(&mut reader)?;
+        connector.load_state(&mut reader)?;
+        reader.finish()?;
+        self.cpu = cpu;
+        self.connector = connector;
+        self.cycles_overrun = 0;
+        Ok(())
+    }
+
     pub fn get_sound_timer(&self) -> u8 {
         self.connector.get_sound_timer()
     }
+
+    pub fn get_delay_timer(&self) -> u8 {
+        self.connector.get_delay_timer()
+    }
+
+    pub fn get_registers(&self) -> &[u8; 16] {
+        self.cpu.get_registers()
+    }
+
+    pub fn get_register(&self, x: u8) -> u8 {
+        self.cpu.get_registers()[x as usize]
+    }
+
+    pub fn change_register(&mut self, x: u8, value: u8) {
+        self.cpu.write_reg_vx(x, value);
+    }
+
+    pub fn get_pc(&self) -> u16 {
+        self.cpu.get_pc()
+    }
+
+    pub fn change_pc(&mut self, pc: u16) {
+        self.cpu.change_pc(pc);
+    }
+
+    pub fn get_i(&self) -> u16 {
+        self.cpu.get_i()
+    }
+
+    pub fn change_i(&mut self, i: u16) {
+        self.cpu.change_i(i);
+    }
+
+    // RETURN ADDRESSES OF THE PENDING SUBROUTINE CALLS, THE LAST ONE BEING THE TOP
+    pub fn get_stack(&self) -> &[u16] {
+        self.cpu.get_stack()
+    }
+
+    // FAILS WITH StackOverflow WHEN THE STACK IS DEEPER THAN THE CONFIGURED DEPTH
+    pub fn change_stack(&mut self, stack: &[u16]) -> Result<(), Chip8Error> {
+        self.cpu.change_stack(&mut self.connector, stack)
+    }
+
+    // PENDING SUBROUTINE CALLS FOR DEBUGGERS, THE LAST ONE BEING THE INNERMOST
+    pub fn get_call_stack(&self) -> Vec<CallFrame> {
+        let memory_size = self.connector.get_memory_size();
+        self.cpu
+            .get_stack()
+            .iter()
+            .map(|&return_address| CallFrame {
+                caller: ((return_address as usize + memory_size - 2) % memory_size) as u16,
+                return_address,
+            })
+            .collect()
+    }
+
+    pub fn change_delay_timer(&mut self, value: u8) {
+        self.connector.change_delay_timer(value);
+    }
+
+    pub fn change_sound_timer(&mut self, value: u8) {
+        self.connector.change_sound_timer(value);
+    }
+
+    // THE WHOLE MEMORY, 4 KB OR 64 KB DEPENDING ON THE ADDRESSING
+    // THE INSTRUCTION AT address IS KEPT DECODED BY THE DECODE CACHE
+    pub fn is_decoded(&self, address: u16) -> bool {
+        self.connector.get_decoded(address).is_some()
+    }
+
+    pub fn get_memory(&self) -> &[u8] {
+        self.connector.get_memory()
+    }
+
+    pub fn read_memory(&self, address: u16) -> Result<u8, Chip8Error> {
+        self.connector.read_byte_ram(address)
+    }
+
+    pub fn write_memory(&mut self, address: u16, value: u8) -> Result<(), Chip8Error> {
+        self.connector.write_byte_ram(address, value)
+    }
+
+    // OPCODE OF THE NEXT INSTRUCTION TO BE EXECUTED
+    pub fn get_current_opcode(&self) -> Result<u16, Chip8Error> {
+        self.cpu.peek_opcode(&self.connector)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,205 @@
+use crate::chip8::INSTRUCTIONS_PER_FRAME;
+use crate::ram::MEMORY_SIZE;
+
+// BEHAVIOURS THAT DIFFER BETWEEN CHIP-8 INTERPRETERS. GAMES ARE WRITTEN FOR ONE OF THEM,
+// SO THE RIGHT SET HAS TO BE PICKED FOR EACH ROM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quirks {
+    // 8XY1, 8XY2 AND 8XY3 RESET VF TO 0
+    pub vf_reset: bool,
+    // FX55 AND FX65 LEAVE I POINTING AFTER THE LAST REGISTER
+    pub memory_increments_i: bool,
+    // 8XY6 AND 8XYE SHIFT VY INTO VX INSTEAD OF SHIFTING VX IN PLACE
+    pub shift_uses_vy: bool,
+    // BNNN JUMPS TO XNN + VX INSTEAD OF NNN + V0
+    pub jump_uses_vx: bool,
+    // SPRITES ARE CUT AT THE SCREEN EDGES INSTEAD OF WRAPPING AROUND
+    pub clip_sprites: bool,
+    // FX1E SETS VF TO 1 WHEN I GOES PAST THE END OF MEMORY AND TO 0 OTHERWISE (AMIGA
+    // INTERPRETER, SPACEFIGHT 2091! DEPENDS ON IT)
+    pub fx1e_sets_vf: bool,
+}
+
+impl Quirks {
+    // BEHAVIOUR OF THIS EMULATOR BEFORE QUIRKS WERE CONFIGURABLE
+    pub fn legacy() -> Quirks {
+        Quirks {
+            vf_reset: false,
+            memory_increments_i: false,
+            shift_uses_vy: false,
+            jump_uses_vx: false,
+            clip_sprites: false,
+            fx1e_sets_vf: false,
+        }
+    }
+
+    // ORIGINAL COSMAC VIP INTERPRETER
+    pub fn chip8() -> Quirks {
+        Quirks {
+            vf_reset: true,
+            memory_increments_i: true,
+            shift_uses_vy: true,
+            jump_uses_vx: false,
+            clip_sprites: true,
+            fx1e_sets_vf: false,
+        }
+    }
+
+    // SUPER-CHIP 1.1 ON THE HP48
+    pub fn schip() -> Quirks {
+        Quirks {
+            vf_reset: false,
+            memory_increments_i: false,
+            shift_uses_vy: false,
+            jump_uses_vx: true,
+            clip_sprites: true,
+            fx1e_sets_vf: false,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Quirks> {
+        match name {
+            "legacy" => Some(Quirks::legacy()),
+            "chip8" => Some(Quirks::chip8()),
+            "schip" => Some(Quirks::schip()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Quirks {
+    fn default() -> Quirks {
+        Quirks::legacy()
+    }
+}
+
+// WHAT HAPPENS TO AN ADDRESS PAST THE END OF MEMORY, E.G. I + X IN FX55 OR NNN + V0 IN BNNN
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Addressing {
+    // 4 KB OF MEMORY, ADDRESSES WRAP AROUND AT 0x1000
+    #[default]
+    Wrap12,
+    // 64 KB OF MEMORY, ADDRESSES WRAP AROUND AT 0x10000 (XO-CHIP)
+    Wrap16,
+    // 4 KB OF MEMORY, ACCESSING PAST THE END STOPS THE PROGRAM WITH AN ERROR
+    Trap,
+}
+
+impl Addressing {
+    pub fn memory_size(self) -> usize {
+        match self {
+            Addressing::Wrap12 | Addressing::Trap => MEMORY_SIZE,
+            Addressing::Wrap16 => 0x10000,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Addressing> {
+        match name {
+            "wrap12" => Some(Addressing::Wrap12),
+            "wrap16" => Some(Addressing::Wrap16),
+            "trap" => Some(Addressing::Trap),
+            _ => None,
+        }
+    }
+}
+
+// DEEPEST STACK ANY CONFIGURATION CAN ASK FOR
+pub const MAX_STACK_DEPTH: usize = 64;
+
+// HOW MANY SUBROUTINE CALLS CAN BE NESTED AND WHERE THE RETURN ADDRESSES ARE KEPT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stack {
+    // NUMBER OF RETURN ADDRESSES, UP TO MAX_STACK_DEPTH
+    pub depth: usize,
+    // THE RETURN ADDRESSES ARE ALSO WRITTEN TO MEMORY FROM 0xEA0 LIKE ON THE COSMAC VIP, AND
+    // 00EE READS THEM BACK FROM THERE, SO A PROGRAM OVERWRITING THAT AREA CHANGES WHERE IT
+    // RETURNS
+    pub in_ram: bool,
+}
+
+impl Stack {
+    // WHAT THIS EMULATOR ALWAYS ALLOWED
+    pub fn legacy() -> Stack {
+        Stack { depth: 24, in_ram: false }
+    }
+
+    pub fn vip() -> Stack {
+        Stack { depth: 12, in_ram: true }
+    }
+
+    pub fn schip() -> Stack {
+        Stack { depth: 16, in_ram: false }
+    }
+
+    pub fn xochip() -> Stack {
+        Stack { depth: MAX_STACK_DEPTH, in_ram: false }
+    }
+
+    pub fn from_name(name: &str) -> Option<Stack> {
+        match name {
+            "legacy" => Some(Stack::legacy()),
+            "vip" => Some(Stack::vip()),
+            "schip" => Some(Stack::schip()),
+            "xochip" => Some(Stack::xochip()),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Stack {
+    fn default() -> Stack {
+        Stack::legacy()
+    }
+}
+
+// HOW MANY INSTRUCTIONS A 60 HZ FRAME RUNS
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Timing {
+    // instructions_per_frame INSTRUCTIONS, WHATEVER THEY ARE
+    #[default]
+    Fixed,
+    // EACH INSTRUCTION COSTS ROUGHLY WHAT IT DID ON THE COSMAC VIP AND DXYN WAITS FOR THE NEXT
+    // FRAME BEFORE DRAWING. instructions_per_frame IS IGNORED.
+    Vip,
+}
+
+impl Timing {
+    pub fn from_name(name: &str) -> Option<Timing> {
+        match name {
+            "fixed" => Some(Timing::Fixed),
+            "vip" => Some(Timing::Vip),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub quirks: Quirks,
+    // SPEED OF THE CPU, THE TIMERS ALWAYS TICK ONCE PER FRAME
+    pub instructions_per_frame: usize,
+    pub addressing: Addressing,
+    pub stack: Stack,
+    pub timing: Timing,
+    // SKIPS THE REST OF A FRAME SPENT WAITING FOR THE DELAY TIMER OR A KEY. THE RESULTS ARE THE
+    // SAME EITHER WAY, ONLY THE HOST CPU TIME CHANGES.
+    pub skip_idle_loops: bool,
+    // KEEPS EACH DECODED INSTRUCTION UNTIL ITS BYTES ARE WRITTEN INSTEAD OF FETCHING AND
+    // DECODING IT EVERY TIME IT RUNS. THE RESULTS ARE THE SAME, IT IS ONLY THERE TO MEASURE WHAT
+    // THE CACHE SAVES (cargo bench --bench roms).
+    pub decode_cache: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            quirks: Quirks::default(),
+            instructions_per_frame: INSTRUCTIONS_PER_FRAME,
+            addressing: Addressing::default(),
+            stack: Stack::default(),
+            timing: Timing::default(),
+            skip_idle_loops: true,
+            decode_cache: true,
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use crate::ram::Ram;
 use crate::input::Input;
 use crate::display::Display;
-use minifb::{Key};
+use crate::error::Chip8Error;
+use crate::instruction::Instruction;
+use crate::state::{StateError, StateReader, StateWriter};
 
 pub struct Connector {
     ram: Ram,
@@ -12,9 +14,9 @@ pub struct Connector {
 }
 
 impl Connector {
-    pub fn new() -> Connector {
+    pub fn new(memory_size: usize) -> Connector {
         Connector {
-            ram: Ram::new(),
+            ram: Ram::new(memory_size),
             input: Input::new(),
             display: Display::new(),
             delay_timer: 0,
@@ -22,16 +24,40 @@ impl Connector {
         }
     }
 
-    pub fn read_byte_ram(&self, address: u16) -> u8 {
+    pub fn get_memory_size(&self) -> usize {
+        self.ram.get_size()
+    }
+
+    pub fn change_memory_size(&mut self, size: usize) {
+        self.ram.change_size(size);
+    }
+
+    pub fn get_memory(&self) -> &[u8] {
+        self.ram.get_bytes()
+    }
+
+    pub fn read_byte_ram(&self, address: u16) -> Result<u8, Chip8Error> {
         self.ram.read_byte(address)
     }
 
-    pub fn write_byte_ram(&mut self, address: u16, value: u8) {
+    pub fn write_byte_ram(&mut self, address: u16, value: u8) -> Result<(), Chip8Error> {
         self.ram.write_byte(address, value)
     }
 
-    pub fn debug_draw_byte(&mut self, b: u8, x: u8, y: u8) -> bool {
-        self.display.debug_draw_sprite(b, x, y)
+    pub fn change_decode_cache(&mut self, enabled: bool) {
+        self.ram.change_decode_cache(enabled);
+    }
+
+    pub fn get_decoded(&self, address: u16) -> Option<Instruction> {
+        self.ram.get_decoded(address)
+    }
+
+    pub fn cache_decoded(&mut self, address: u16, instruction: Instruction) {
+        self.ram.cache_decoded(address, instruction);
+    }
+
+    pub fn debug_draw_byte(&mut self, b: u8, x: usize, y: usize, clip: bool) -> bool {
+        self.display.debug_draw_sprite(b, x, y, clip)
     }
 
     pub fn clear_screen(&mut self) {
@@ -42,6 +68,18 @@ impl Connector {
         self.display.get_display()
     }
 
+    pub fn is_display_dirty(&self) -> bool {
+        self.display.is_dirty()
+    }
+
+    pub fn is_display_row_dirty(&self, y: usize) -> bool {
+        self.display.is_row_dirty(y)
+    }
+
+    pub fn clear_display_dirty(&mut self) {
+        self.display.clear_dirty();
+    }
+
     pub fn change_key_pressed(&mut self, key: Option<u8>) {
         self.input.change_key_pressed(key);
     }
@@ -54,37 +92,12 @@ impl Connector {
         self.input.get_key_pressed()
     }
 
-    pub fn get_keycode_by_key(key: Option<Key>) -> Option<u8> {
-        match key {
-            // ORIGINAL : 1 2 3 C
-            // EMULATOR : A Z E R
-            Some(Key::A) => Some(0x1),
-            Some(Key::Z) => Some(0x2),
-            Some(Key::E) => Some(0x3),
-            Some(Key::R) => Some(0xC),
-
-            // ORIGINAL : 4 5 6 D
-            // EMULATOR : Q S D F
-            Some(Key::Q) => Some(0x4),
-            Some(Key::S) => Some(0x5),
-            Some(Key::D) => Some(0x6),
-            Some(Key::F) => Some(0xD),
-
-            // ORIGINAL : 7 8 9 E
-            // EMULATOR : W X C V
-            Some(Key::W) => Some(0x7),
-            Some(Key::X) => Some(0x8),
-            Some(Key::C) => Some(0x9),
-            Some(Key::V) => Some(0xE),
-
-            // ORIGINAL : A 0 B F
-            // EMULATOR : U I O P
-            Some(Key::U) => Some(0xA),
-            Some(Key::I) => Some(0x0),
-            Some(Key::O) => Some(0xB),
-            Some(Key::P) => Some(0xF),
-            _ => None,
-        }
+    pub fn change_keys_pressed(&mut self, mask: u16) {
+        self.input.change_keys_pressed(mask);
+    }
+
+    pub fn get_keys_pressed(&self) -> u16 {
+        self.input.get_keys_pressed()
     }
 
     pub fn get_delay_timer(&self) -> u8 {
@@ -103,9 +116,33 @@ impl Connector {
         self.sound_timer = value;
     }
 
+    pub fn is_sound_active(&self) -> bool {
+        self.sound_timer > 0
+    }
+
     pub fn tick(&mut self) {
         if self.delay_timer > 0 {
             self.delay_timer -= 1;
         }
+        if self.sound_timer > 0 {
+            self.sound_timer -= 1;
+        }
+    }
+
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        self.ram.save_state(writer);
+        self.display.save_state(writer);
+        writer.write_u16(self.input.get_keys_pressed());
+        writer.write_u8(self.delay_timer);
+        writer.write_u8(self.sound_timer);
+    }
+
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.ram.load_state(reader)?;
+        self.display.load_state(reader)?;
+        self.input.change_keys_pressed(reader.read_u16()?);
+        self.delay_timer = reader.read_u8()?;
+        self.sound_timer = reader.read_u8()?;
+        Ok(())
     }
 }
@@ -0,0 +1,295 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use crate::chip8::{MEMORY_SIZE, PROGRAM_START};
+use crate::disasm;
+
+// STATIC CONTROL-FLOW GRAPH OF A ROM, FOUND BY FOLLOWING EVERY PATH FROM THE ENTRY POINT
+// WITHOUT RUNNING ANYTHING. BNNN JUMPS DEPEND ON V0 AND ARE ONLY FLAGGED, WHAT THEY REACH
+// IS NOT IN THE GRAPH. 00EE ENDS ITS BLOCK WITHOUT EDGES: THE CALL SITES HAVE THE EDGE TO
+// WHERE IT RETURNS.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    // 1NNN
+    Jump,
+    // INTO THE NEXT BLOCK, WHICH STARTS RIGHT AFTER
+    Fallthrough,
+    // 2NNN INTO THE SUBROUTINE
+    Call,
+    // FROM A 2NNN TO WHERE THE SUBROUTINE RETURNS
+    Return,
+    // A SKIP INSTRUCTION, TO THE INSTRUCTION AFTER THE NEXT ONE
+    Skip,
+    // A SKIP INSTRUCTION, TO THE NEXT INSTRUCTION
+    NoSkip,
+}
+
+impl EdgeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            EdgeKind::Jump => "jump",
+            EdgeKind::Fallthrough => "fallthrough",
+            EdgeKind::Call => "call",
+            EdgeKind::Return => "return",
+            EdgeKind::Skip => "skip",
+            EdgeKind::NoSkip => "no skip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+    pub target: u16,
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    pub start: u16,
+    // (ADDRESS, OPCODE) OF EACH INSTRUCTION, THE LAST ONE ENDS THE BLOCK
+    pub instructions: Vec<(u16, u16)>,
+    pub successors: Vec<Edge>,
+}
+
+impl BasicBlock {
+    pub fn last_address(&self) -> u16 {
+        self.instructions.last().map_or(self.start, |(address, _)| *address)
+    }
+
+    pub fn contains(&self, address: u16) -> bool {
+        self.instructions.iter().any(|(instruction, _)| *instruction == address)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub address: u16,
+    pub message: String,
+}
+
+pub struct ControlFlowGraph {
+    // SORTED BY START ADDRESS, THE FIRST ONE IS THE ENTRY POINT
+    pub blocks: Vec<BasicBlock>,
+    // ENTRY POINTS OF THE 2NNN TARGETS
+    pub subroutines: Vec<u16>,
+    // ADDRESSES OF THE BNNN JUMPS
+    pub unresolved: Vec<u16>,
+    pub warnings: Vec<Warning>,
+}
+
+impl ControlFlowGraph {
+    // THE ROM IS LOADED AT PROGRAM_START IN 4 KB OF MEMORY, LIKE load_rom DOES
+    pub fn build(rom: &[u8]) -> ControlFlowGraph {
+        let mut memory = vec![0; MEMORY_SIZE];
+        let start = PROGRAM_START as usize;
+        let size = rom.len().min(MEMORY_SIZE - start);
+        memory[start..start + size].copy_from_slice(&rom[..size]);
+
+        // FIRST PASS: EVERY REACHABLE INSTRUCTION AND WHERE BLOCKS HAVE TO START
+        let mut instructions: BTreeMap<u16, u16> = BTreeMap::new();
+        let mut leaders: BTreeSet<u16> = BTreeSet::from([PROGRAM_START]);
+        let mut subroutines = BTreeSet::new();
+        let mut unresolved = Vec::new();
+        let mut pending = vec![PROGRAM_START];
+        while let Some(address) = pending.pop() {
+            if instructions.contains_key(&address) || address as usize + 1 >= MEMORY_SIZE {
+                continue;
+            }
+            let opcode = disasm::opcode_at(&memory, address as usize);
+            instructions.insert(address, opcode);
+            let edges = edges(address, opcode);
+            if ends_block(opcode) {
+                leaders.extend(edges.iter().map(|edge| edge.target));
+            }
+            if opcode >> 12 == 0x2 {
+                subroutines.insert(opcode & 0x0FFF);
+            }
+            if opcode >> 12 == 0xB {
+                unresolved.push(address);
+            }
+            pending.extend(edges.iter().map(|edge| edge.target));
+        }
+
+        // SECOND PASS: CUT THE INSTRUCTIONS INTO BLOCKS AT THE LEADERS
+        let mut blocks = Vec::new();
+        for leader in leaders.iter().filter(|leader| instructions.contains_key(leader)) {
+            let mut block = BasicBlock {
+                start: *leader,
+                instructions: Vec::new(),
+                successors: Vec::new(),
+            };
+            let mut address = *leader;
+            while let Some(opcode) = instructions.get(&address) {
+                block.instructions.push((address, *opcode));
+                let next = address + 2;
+                if ends_block(*opcode) {
+                    block.successors = edges(address, *opcode);
+                    break;
+                }
+                if leaders.contains(&next) {
+                    block.successors = vec![Edge { target: next, kind: EdgeKind::Fallthrough }];
+                    break;
+                }
+                address = next;
+            }
+            blocks.push(block);
+        }
+
+        unresolved.sort();
+        let warnings = self_modifying_writes(&blocks, &instructions);
+        ControlFlowGraph {
+            blocks,
+            subroutines: subroutines.into_iter().collect(),
+            unresolved,
+            warnings,
+        }
+    }
+
+    pub fn get_block(&self, start: u16) -> Option<&BasicBlock> {
+        self.blocks.iter().find(|block| block.start == start)
+    }
+
+    // GRAPHVIZ: dot -Tsvg graph.dot > graph.svg
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph rom {\n    node [shape=box, fontname=\"monospace\"];\n");
+        for block in &self.blocks {
+            let mut label = String::new();
+            for (address, opcode) in &block.instructions {
+                let _ = write!(label, "{:04X}  {}\\l", address, disasm::disassemble(*opcode));
+            }
+            let warned = self.warnings.iter().any(|warning| block.contains(warning.address));
+            let unresolved = self.unresolved.contains(&block.last_address());
+            let style = if warned || unresolved { ", style=filled, fillcolor=\"#ffc0c0\"" } else { "" };
+            let _ = writeln!(dot, "    b{:04X} [label=\"{}\"{}];", block.start, label, style);
+            for edge in &block.successors {
+                let style = match edge.kind {
+                    EdgeKind::Return => ", style=dashed",
+                    EdgeKind::Call => ", style=bold",
+                    _ => "",
+                };
+                let _ = writeln!(dot, "    b{:04X} -> b{:04X} [label=\"{}\"{}];", block.start, edge.target, edge.kind.name(), style);
+            }
+        }
+        for warning in &self.warnings {
+            let _ = writeln!(dot, "    // {:04X}: {}", warning.address, warning.message);
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    // ADDRESSES AND OPCODES AS NUMBERS, e.g.
+    // {"entry": 512, "blocks": [{"start": 512, "instructions": [...], "successors": [...]}], ...}
+    pub fn to_json(&self) -> String {
+        let blocks: Vec<String> = self
+            .blocks
+            .iter()
+            .map(|block| {
+                let instructions: Vec<String> = block
+                    .instructions
+                    .iter()
+                    .map(|(address, opcode)| {
+                        format!(
+                            "{{\"address\": {}, \"opcode\": {}, \"text\": {}}}",
+                            address,
+                            opcode,
+                            json_string(&disasm::disassemble(*opcode)),
+                        )
+                    })
+                    .collect();
+                let successors: Vec<String> = block
+                    .successors
+                    .iter()
+                    .map(|edge| format!("{{\"target\": {}, \"kind\": {}}}", edge.target, json_string(edge.kind.name())))
+                    .collect();
+                format!(
+                    "{{\"start\": {}, \"instructions\": [{}], \"successors\": [{}]}}",
+                    block.start,
+                    instructions.join(", "),
+                    successors.join(", "),
+                )
+            })
+            .collect();
+        let numbers = |values: &[u16]| values.iter().map(|value| value.to_string()).collect::<Vec<_>>().join(", ");
+        let warnings: Vec<String> = self
+            .warnings
+            .iter()
+            .map(|warning| format!("{{\"address\": {}, \"message\": {}}}", warning.address, json_string(&warning.message)))
+            .collect();
+        format!(
+            "{{\"entry\": {}, \"blocks\": [{}], \"subroutines\": [{}], \"unresolved\": [{}], \"warnings\": [{}]}}\n",
+            PROGRAM_START,
+            blocks.join(", "),
+            numbers(&self.subroutines),
+            numbers(&self.unresolved),
+            warnings.join(", "),
+        )
+    }
+}
+
+// WHERE THE INSTRUCTION AT address CAN GO NEXT, AS FAR AS THE ROM ALONE TELLS
+fn edges(address: u16, opcode: u16) -> Vec<Edge> {
+    let next = address + 2;
+    let edge = |target: u16, kind: EdgeKind| Edge { target, kind };
+    match opcode >> 12 {
+        _ if !disasm::is_instruction(opcode) => Vec::new(),
+        0x0 if opcode & 0xFF == 0xEE => Vec::new(),
+        0x1 => vec![edge(opcode & 0x0FFF, EdgeKind::Jump)],
+        0x2 => vec![edge(opcode & 0x0FFF, EdgeKind::Call), edge(next, EdgeKind::Return)],
+        0xB => Vec::new(),
+        _ if disasm::is_skip(opcode) => vec![edge(next, EdgeKind::NoSkip), edge(address + 4, EdgeKind::Skip)],
+        _ => vec![edge(next, EdgeKind::Fallthrough)],
+    }
+}
+
+// JUMPS, CALLS, RETURNS, SKIPS AND WHAT THE CPU CANNOT RUN
+fn ends_block(opcode: u16) -> bool {
+    !disasm::is_instruction(opcode) || opcode & 0xF0FF == 0x00EE || disasm::is_skip(opcode) || matches!(opcode >> 12, 0x1 | 0x2 | 0xB)
+}
+
+// FX33 AND FX55 WRITING OVER INSTRUCTIONS, WHEN AN ANNN EARLIER IN THE SAME BLOCK TELLS
+// WHERE I POINTS. FX1E AND FX29 MOVE I, AND FX55 AND FX65 MAY MOVE IT DEPENDING ON THE QUIRKS,
+// SO I IS UNKNOWN AFTER THEM UNTIL THE NEXT ANNN.
+fn self_modifying_writes(blocks: &[BasicBlock], instructions: &BTreeMap<u16, u16>) -> Vec<Warning> {
+    let is_code = |address: usize| {
+        let address = address as u16;
+        instructions.contains_key(&address) || instructions.contains_key(&address.wrapping_sub(1))
+    };
+    let mut warnings = Vec::new();
+    for block in blocks {
+        let mut i = None;
+        for (address, opcode) in &block.instructions {
+            let x = ((opcode & 0x0F00) >> 8) as usize;
+            let written = match (opcode >> 12, opcode & 0xFF) {
+                (0xA, _) => {
+                    i = Some((opcode & 0x0FFF) as usize);
+                    None
+                },
+                (0xF, 0x1E | 0x29 | 0x65) => {
+                    i = None;
+                    None
+                },
+                (0xF, 0x33) => i.map(|i| (i, 3)),
+                (0xF, 0x55) => i.take().map(|i| (i, x + 1)),
+                _ => None,
+            };
+            if let Some((first, count)) = written {
+                if (first..first + count).any(is_code) {
+                    warnings.push(Warning {
+                        address: *address,
+                        message: format!(
+                            "{} writes {:04X}-{:04X}, which holds code",
+                            disasm::disassemble(*opcode),
+                            first,
+                            first + count - 1,
+                        ),
+                    });
+                }
+            }
+        }
+    }
+    warnings
+}
+
+fn json_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,145 @@
+use std::fmt::Write;
+use crate::disasm;
+
+// WHAT HAPPENED TO EACH BYTE OF MEMORY SINCE COVERAGE STARTED, AS BIT FLAGS
+pub const EXECUTED: u8 = 0x01;
+// READ BY DXYN, FX65 THROUGH I
+pub const READ: u8 = 0x02;
+// WRITTEN BY FX33, FX55 THROUGH I
+pub const WRITTEN: u8 = 0x04;
+// ON THE FIRST BYTE OF A SKIP INSTRUCTION: IT SKIPPED, IT DID NOT
+pub const SKIP_TAKEN: u8 = 0x08;
+pub const SKIP_NOT_TAKEN: u8 = 0x10;
+
+// TRACKS WHICH BYTES WERE EXECUTED AS CODE, READ AS DATA AND WRITTEN, AND WHICH WAY EACH SKIP
+// WENT, TO FIND DEAD CODE AND BRANCHES A TEST SCENARIO NEVER TOOK. THE RETURN STACK THE VIP
+// KEEPS IN MEMORY IS THE INTERPRETER'S, NOT THE ROM'S, AND IS NOT TRACKED.
+pub struct Coverage {
+    flags: Vec<u8>,
+}
+
+impl Coverage {
+    pub fn new(memory_size: usize) -> Coverage {
+        Coverage {
+            flags: vec![0; memory_size],
+        }
+    }
+
+    // CALLED AFTER EACH INSTRUCTION THAT RAN, WITH THE PC, OPCODE AND I IT RAN WITH AND THE
+    // PC IT LEFT
+    pub fn record(&mut self, pc: u16, opcode: u16, i: u16, next_pc: u16) {
+        let x = ((opcode & 0x0F00) >> 8) as usize;
+        self.mark(pc as usize, 2, EXECUTED);
+        if disasm::is_skip(opcode) {
+            let taken = next_pc == pc.wrapping_add(4);
+            self.mark(pc as usize, 1, if taken { SKIP_TAKEN } else { SKIP_NOT_TAKEN });
+        }
+        match (opcode >> 12, opcode & 0xFF) {
+            (0xD, _) => self.mark(i as usize, (opcode & 0xF) as usize, READ),
+            (0xF, 0x33) => self.mark(i as usize, 3, WRITTEN),
+            (0xF, 0x55) => self.mark(i as usize, x + 1, WRITTEN),
+            (0xF, 0x65) => self.mark(i as usize, x + 1, READ),
+            _ => {},
+        }
+    }
+
+    // ADDRESSES WRAP AT THE END OF MEMORY LIKE THE CPU'S
+    fn mark(&mut self, address: usize, count: usize, flag: u8) {
+        let size = self.flags.len();
+        for offset in 0..count {
+            self.flags[(address + offset) % size] |= flag;
+        }
+    }
+
+    pub fn get_flags(&self, address: usize) -> u8 {
+        self.flags.get(address).copied().unwrap_or(0)
+    }
+
+    pub fn is_executed(&self, address: usize) -> bool {
+        self.get_flags(address) & EXECUTED != 0
+    }
+
+    // RANGES OF start..end NEVER EXECUTED, READ OR WRITTEN, AS INCLUSIVE (FIRST, LAST) PAIRS
+    pub fn untouched(&self, start: usize, end: usize) -> Vec<(usize, usize)> {
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        for address in start..end.min(self.flags.len()) {
+            if self.flags[address] != 0 {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(last) if last.1 + 1 == address => last.1 = address,
+                _ => ranges.push((address, address)),
+            }
+        }
+        ranges
+    }
+
+    // ADDRESSES OF THE SKIPS IN start..end THAT RAN BUT ONLY EVER WENT ONE WAY
+    pub fn partial_branches(&self, start: usize, end: usize) -> Vec<usize> {
+        (start..end.min(self.flags.len()))
+            .filter(|address| matches!(self.flags[*address] & (SKIP_TAKEN | SKIP_NOT_TAKEN), SKIP_TAKEN | SKIP_NOT_TAKEN))
+            .collect()
+    }
+
+    // SUMMARY OF start..end, USUALLY THE ROM: HOW MUCH OF IT WAS USED AND WHAT WAS NOT
+    pub fn report(&self, memory: &[u8], start: usize, end: usize) -> String {
+        let end = end.min(self.flags.len()).min(memory.len());
+        let start = start.min(end);
+        let count = |flag: u8| self.flags[start..end].iter().filter(|flags| *flags & flag != 0).count();
+        let percent = |count: usize| count as f64 * 100.0 / (end - start).max(1) as f64;
+        let mut report = String::new();
+        let _ = writeln!(report, "{:04X}-{:04X}, {} bytes", start, end.saturating_sub(1), end - start);
+        for (name, flag) in [("executed", EXECUTED), ("read", READ), ("written", WRITTEN)] {
+            let _ = writeln!(report, "  {:<8} {:>5} bytes  {:5.1}%", name, count(flag), percent(count(flag)));
+        }
+
+        let _ = writeln!(report, "\nNever used");
+        for (first, last) in self.untouched(start, end) {
+            let _ = writeln!(report, "  {:04X}-{:04X}  {:>5} bytes", first, last, last - first + 1);
+        }
+
+        let _ = writeln!(report, "\nSkips that only went one way");
+        for address in self.partial_branches(start, end) {
+            let way = if self.flags[address] & SKIP_TAKEN != 0 { "always skipped" } else { "never skipped" };
+            let opcode = disasm::opcode_at(memory, address);
+            let _ = writeln!(report, "  {:04X}  {:<16} {}", address, disasm::disassemble(opcode), way);
+        }
+        report
+    }
+
+    // DISASSEMBLY OF start..end WITH THE FLAGS OF EACH WORD, e.g.
+    // 0204  3000  SE V0, 0x00       X--  never skipped
+    // X, R AND W FOR EXECUTED, READ AND WRITTEN. CODE THAT RAN AT AN ODD ADDRESS IS FOLLOWED.
+    pub fn listing(&self, memory: &[u8], start: usize, end: usize) -> String {
+        let end = end.min(self.flags.len()).min(memory.len());
+        let mut listing = String::new();
+        let mut address = start;
+        while address < end {
+            // ONE BYTE ON ITS OWN WHEN CODE STARTS AT THE NEXT ONE
+            let single = address + 1 == end || (!self.is_executed(address) && self.is_executed(address + 1));
+            let size = if single { 1 } else { 2 };
+            let flags = self.flags[address..address + size].iter().fold(0, |all, flags| all | flags);
+            let marks: String = [(EXECUTED, 'X'), (READ, 'R'), (WRITTEN, 'W')]
+                .iter()
+                .map(|(flag, mark)| if flags & flag != 0 { *mark } else { '-' })
+                .collect();
+
+            if single {
+                let byte = memory[address];
+                let data = format!("DB 0x{:02X}", byte);
+                let _ = writeln!(listing, "{:04X}  {:02X}    {:<16}  {}", address, byte, data, marks);
+            } else {
+                let opcode = disasm::opcode_at(memory, address);
+                let mut line = format!("{:04X}  {:04X}  {:<16}  {}", address, opcode, disasm::disassemble(opcode), marks);
+                line.push_str(match flags & (SKIP_TAKEN | SKIP_NOT_TAKEN) {
+                    SKIP_TAKEN => "  always skipped",
+                    SKIP_NOT_TAKEN => "  never skipped",
+                    _ => "",
+                });
+                let _ = writeln!(listing, "{}", line);
+            }
+            address += size;
+        }
+        listing
+    }
+}
@@ -1,310 +1,381 @@
+use crate::config::{Addressing, Quirks, Stack, MAX_STACK_DEPTH};
 use crate::connector::Connector;
-use rand::Rng;
+use crate::error::Chip8Error;
+use crate::display::{HEIGHT_SCREEN, WIDTH_SCREEN};
+use crate::instruction::Instruction;
+use crate::ram::MEMORY_SIZE;
+use crate::random::{self, Random};
+use crate::state::{StateError, StateReader, StateWriter};
 
 pub(crate) const START_ADDRESS: u16 = 0x200;
+// WHERE THE COSMAC VIP KEEPS ITS STACK, TWO BYTES PER RETURN ADDRESS
+const VIP_STACK_ADDRESS: u16 = 0xEA0;
+
 pub struct Cpu {
     // 16 REGISTERS V0 TO VF
     vx: [u8; 16],
     pc: u16,
     i: u16,
-    // CONTAINS ADDRESS TO RETURNS FROM SUB ROUTINES
-    ret_stack: Vec<u16>,
+    // CONTAINS ADDRESS TO RETURNS FROM SUB ROUTINES, THE FIRST sp ENTRIES ARE IN USE
+    ret_stack: [u16; MAX_STACK_DEPTH],
+    sp: usize,
+    // SOURCE FOR CXNN
+    random: Random,
+    quirks: Quirks,
+    addressing: Addressing,
+    stack: Stack,
 }
 
 impl Cpu {
-    pub fn new() -> Cpu {
+    pub fn new(quirks: Quirks, addressing: Addressing, stack: Stack) -> Cpu {
         Cpu {
             vx: [0; 16],
             pc: START_ADDRESS,
             i: 0,
-            ret_stack: Vec::<u16>::new(),
+            ret_stack: [0; MAX_STACK_DEPTH],
+            sp: 0,
+            random: Random::new(random::DEFAULT_SEED),
+            quirks,
+            addressing,
+            stack,
         }
     }
 
-    pub fn run_instruction(&mut self, connector: &mut Connector) {
-        let high = connector.read_byte_ram(self.pc) as u16;
-        let low = connector.read_byte_ram(self.pc + 1) as u16;
-        let instruction: u16 =  (high << 8) | low;
-        let nnn = instruction & 0x0FFF;
-        let nn = (instruction & 0x0FF) as u8;
-        let n = (instruction & 0x00F) as u8;
-        let x = ((instruction & 0x0F00) >> 8) as u8;
-        let y = ((instruction & 0x00F0) >> 4) as u8;
-
-        if self.ret_stack.len() > 24 {
-            panic!("Too much subroutines. Only 24 are allowed.")
-        }
+    // WITH THE DECODE CACHE, AN INSTRUCTION IS ONLY FETCHED AND DECODED THE FIRST TIME, UNTIL
+    // ITS BYTES ARE WRITTEN
+    pub fn run_instruction(&mut self, connector: &mut Connector) -> Result<(), Chip8Error> {
+        // PC IS BROUGHT BACK INTO MEMORY FIRST, SO IT WRAPS AROUND LIKE ANY OTHER ADDRESS
+        self.pc = self.address(self.pc, 0)?;
+        let instruction = match connector.get_decoded(self.pc) {
+            Some(instruction) => instruction,
+            None => {
+                let instruction = Instruction::decode(self.peek_opcode(connector)?);
+                connector.cache_decoded(self.pc, instruction);
+                instruction
+            },
+        };
+        self.execute(connector, instruction)
+    }
 
-        match (instruction & 0xF000) >> 12 {
-            0x0 => {
-                match nn {
-                    0xEE => {
-                        // RETURNS FROM A SUBROUTINE
-                        let addr = self.ret_stack.pop().unwrap();
-                        self.pc = addr;
-                    },
-                    0xE0 => {
-                        // CLEARS THE SCREEN
-                        connector.clear_screen();
-                        self.pc += 2;
-                    },
-                    _ => {
-                        panic!("Unknown instruction for 0x0: {:#X}", nn);
-                    },
-                }
+    // RUNS AN INSTRUCTION ALREADY FETCHED FROM PC, WHICH MUST BE INSIDE MEMORY
+    pub(crate) fn execute(&mut self, connector: &mut Connector, instruction: Instruction) -> Result<(), Chip8Error> {
+        match instruction {
+            Instruction::Return => {
+                // RETURNS FROM A SUBROUTINE
+                self.pc = self.pop_stack(connector)?;
+            },
+            Instruction::ClearScreen => {
+                // CLEARS THE SCREEN
+                connector.clear_screen();
+                self.pc = self.wrap_pc(self.pc, 2);
             },
-            0x1 => {
+            Instruction::Jump(nnn) => {
                 // JUMPS TO NNN
                 self.pc = nnn;
             },
-            0x2 => {
+            Instruction::Call(nnn) => {
                 // CALLS SUBROUTINE AT NNN
-                self.ret_stack.push(self.pc + 2);
+                self.push_stack(connector, self.wrap_pc(self.pc, 2))?;
                 self.pc = nnn;
             },
-            0x3 => {
+            Instruction::SkipEqual(x, nn) => {
                 // SKIPS THE NEXT INSTRUCTION IF VX EQUALS NN
                 let temp = self.read_reg_vx(x);
-                if temp == nn {
-                    self.pc += 4;
-                } else {
-                    self.pc += 2;
-                }
+                self.skip_if(temp == nn);
             },
-            0x4 => {
+            Instruction::SkipNotEqual(x, nn) => {
                 // SKIPS THE NEXT INSTRUCTION IF VX DOES NOT EQUAL NN
                 let temp = self.read_reg_vx(x);
-                if temp != nn {
-                    self.pc += 4;
-                } else {
-                    self.pc += 2;
-                }
+                self.skip_if(temp != nn);
             },
-            0x5 => {
-              // SKIPS THE NEXT INSTRUCTION IF VX EQUALS VY
+            Instruction::SkipEqualRegisters(x, y) => {
+                // SKIPS THE NEXT INSTRUCTION IF VX EQUALS VY
                 let temp_x = self.read_reg_vx(x);
                 let temp_y = self.read_reg_vx(y);
-                if temp_x == temp_y {
-                    self.pc += 4;
-                } else {
-                    self.pc += 2;
-                }
+                self.skip_if(temp_x == temp_y);
             },
-            0x6 => {
+            Instruction::Load(x, nn) => {
                 // SETS VX TO NN
                 self.write_reg_vx(x, nn);
-                self.pc += 2;
+                self.pc = self.wrap_pc(self.pc, 2);
             },
-            0x7 => {
+            Instruction::Add(x, nn) => {
                 // ADDS NN TO VX
                 let temp = self.read_reg_vx(x);
                 self.write_reg_vx(x, temp.wrapping_add(nn));
-                self.pc += 2;
+                self.pc = self.wrap_pc(self.pc, 2);
             }
-            0x8 => {
-                let temp_x = self.read_reg_vx(x);
+            Instruction::Move(x, y) => {
+                // SETS VX TO VY
                 let temp_y = self.read_reg_vx(y);
-                match n {
-                    0x0 => {
-                        // SETS VX TO VY
-                        self.write_reg_vx(x, temp_y);
-                    },
-                    0x1 => {
-                        // SETS VX TO BITWISE OPERATION VX or VY
-                        self.write_reg_vx(x, temp_x | temp_y);
-                    },
-                    0x2 => {
-                        // SETS VX TO BITWISE OPERATION VX and VY
-                        self.write_reg_vx(x, temp_x & temp_y);
-                    },
-                    0x3 => {
-                        // SETS VX TO BITWISE OPERATION VX xor VY
-                        self.write_reg_vx(x, temp_x ^ temp_y);
-                    },
-                    0x4 => {
-                        // ADDS VY TO VX. VF IS SET TO 1 IF THERE'S A CARRY, 0 WHEN THERE IS NOT
-                        let (total, overflow) = temp_x.overflowing_add(temp_y);
-                        self.write_reg_vx(x, total);
-                        self.write_reg_vx(0xF, overflow as u8);
-                    },
-                    0x5 => {
-                        // SUBTRACTS VY OF VX. VF IS SET TO 0 IF THERE'S A BORROW,
-                        // 0 WHEN THERE'S NONE
-                        let (total, underflow) = temp_x.overflowing_sub(temp_y);
-                        self.write_reg_vx(x, total);
-                        self.write_reg_vx(0xF, underflow as u8);
-                    },
-                    0x6 => {
-                        // Stores the least significant bit of VX in VF and then shifts
-                        // VX to the right by 1.
-                        self.write_reg_vx(0xF, temp_x & 0x1);
-                        self.write_reg_vx(x, temp_x >> 1);
-                    },
-                    0x7 => {
-                        // SETS VX TO VY MINUS VX. VF IS SET TO 0 WHEN THERE'S A BORROW AND 1 WHEN
-                        // THERE'S NONE
-                        let (total, underflow) = temp_y.overflowing_sub(temp_x);
-                        self.write_reg_vx(x, total);
-                        self.write_reg_vx(0xF, underflow as u8);
-                    },
-                    0xE => {
-                        // STORES THE MOST SIGNIFICANT BIT OF VX IN VF AND THEN SHIFTS VX TO THE
-                        // LEFT BY 1
-                        let most = temp_x >> 7;
-                        let shifted = temp_x << 1;
-                        self.write_reg_vx(0xF, most);
-                        self.write_reg_vx(x, shifted);
-                    },
-                    _ => {
-                        panic!("Unknown instruction for 0x8.")
-                    },
-                }
-                self.pc += 2;
-            }
-            0x9 => {
+                self.write_reg_vx(x, temp_y);
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::Or(x, y) => {
+                // SETS VX TO BITWISE OPERATION VX or VY
+                let value = self.read_reg_vx(x) | self.read_reg_vx(y);
+                self.write_logic_result(x, value);
+            },
+            Instruction::And(x, y) => {
+                // SETS VX TO BITWISE OPERATION VX and VY
+                let value = self.read_reg_vx(x) & self.read_reg_vx(y);
+                self.write_logic_result(x, value);
+            },
+            Instruction::Xor(x, y) => {
+                // SETS VX TO BITWISE OPERATION VX xor VY
+                let value = self.read_reg_vx(x) ^ self.read_reg_vx(y);
+                self.write_logic_result(x, value);
+            },
+            Instruction::AddRegisters(x, y) => {
+                // ADDS VY TO VX. VF IS SET TO 1 IF THERE'S A CARRY, 0 WHEN THERE IS NOT
+                let (total, overflow) = self.read_reg_vx(x).overflowing_add(self.read_reg_vx(y));
+                self.write_reg_vx(x, total);
+                self.write_reg_vx(0xF, overflow as u8);
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::Sub(x, y) => {
+                // SUBTRACTS VY OF VX. VF IS SET TO 0 IF THERE'S A BORROW,
+                // 1 WHEN THERE'S NONE
+                let (total, underflow) = self.read_reg_vx(x).overflowing_sub(self.read_reg_vx(y));
+                self.write_reg_vx(x, total);
+                self.write_reg_vx(0xF, !underflow as u8);
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::ShiftRight(x, y) => {
+                // Stores the least significant bit of VX in VF and then shifts
+                // VX to the right by 1.
+                let source = if self.quirks.shift_uses_vy { self.read_reg_vx(y) } else { self.read_reg_vx(x) };
+                // VF IS WRITTEN LAST SO THE FLAG WINS WHEN X IS F
+                self.write_reg_vx(x, source >> 1);
+                self.write_reg_vx(0xF, source & 0x1);
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::SubReverse(x, y) => {
+                // SETS VX TO VY MINUS VX. VF IS SET TO 0 WHEN THERE'S A BORROW AND 1 WHEN
+                // THERE'S NONE
+                let (total, underflow) = self.read_reg_vx(y).overflowing_sub(self.read_reg_vx(x));
+                self.write_reg_vx(x, total);
+                self.write_reg_vx(0xF, !underflow as u8);
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::ShiftLeft(x, y) => {
+                // STORES THE MOST SIGNIFICANT BIT OF VX IN VF AND THEN SHIFTS VX TO THE
+                // LEFT BY 1
+                let source = if self.quirks.shift_uses_vy { self.read_reg_vx(y) } else { self.read_reg_vx(x) };
+                let most = source >> 7;
+                let shifted = source << 1;
+                self.write_reg_vx(x, shifted);
+                self.write_reg_vx(0xF, most);
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::SkipNotEqualRegisters(x, y) => {
                 // SKIPS THE NEXT INSTRUCTION IF VX DOES NOT EQUALS VY
                 let temp_x = self.read_reg_vx(x);
                 let temp_y = self.read_reg_vx(y);
-                if temp_x != temp_y {
-                    self.pc += 4;
-                } else {
-                    self.pc += 2;
-                }
+                self.skip_if(temp_x != temp_y);
             },
-            0xA => {
+            Instruction::LoadI(nnn) => {
                 // SETS I TO NNN
                 self.i = nnn;
-                self.pc += 2;
+                self.pc = self.wrap_pc(self.pc, 2);
             },
-            0xB => {
-                // JUMPS TO NNN + V0
-                let temp = self.vx[0] as u16;
-                self.pc = nnn + temp;
+            Instruction::JumpOffset(x, nnn) => {
+                // JUMPS TO NNN + V0 (OR XNN + VX WITH THE SCHIP QUIRK)
+                let offset = if self.quirks.jump_uses_vx { x } else { 0 };
+                let temp = self.vx[offset as usize] as u16;
+                self.pc = self.wrap_pc(nnn, temp);
             },
-            0xC => {
+            Instruction::Random(x, nn) => {
                 // SETS VX TO THE RESULT OF A BITWISE & OPERATION ON A RANDOM NUMBER AND NN
-                let random_number = rand::thread_rng().gen_range(0, 255) as u8;
+                let random_number = self.random.next_byte();
                 self.write_reg_vx(x, random_number & nn);
-                self.pc += 2;
+                self.pc = self.wrap_pc(self.pc, 2);
             }
-            0xD => {
+            Instruction::Draw(x, y, n) => {
                 // DRAWS SPRITE AT COORDINATE (VX, VY) W 8 PIXELS WIDTH AND N PIXELS HEIGHT
                 let temp_x = self.read_reg_vx(x);
                 let temp_y = self.read_reg_vx(y);
-                self.debug_draw_sprite(connector, temp_x, temp_y, n);
-                self.pc += 2;
-            },
-            0xE => {
-                match nn {
-                    0x9E => {
-                        // SKIPS THE NEXT INSTRUCTION IF THE KEY STORED IN VX IS PRESSED
-                        let key = self.read_reg_vx(x);
-                        if connector.is_key_pressed(key) {
-                            self.pc += 4;
-                        } else {
-                            self.pc += 2;
-                        }
-                    },
-                    0xA1 => {
-                        // SKIPS THE NEXT INSTRUCTION IF THE KEY STORED IN VX IS NOT PRESSED
-                        let key = self.read_reg_vx(x);
-                        if !connector.is_key_pressed(key) {
-                            self.pc += 4;
-                        } else {
-                            self.pc += 2;
-                        }
-                    }
-                    _ => {
-                        panic!("Unknow instruction for 0xE.")
-                    },
-                }
+                self.debug_draw_sprite(connector, temp_x, temp_y, n)?;
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::SkipKeyPressed(x) => {
+                // SKIPS THE NEXT INSTRUCTION IF THE KEY STORED IN VX IS PRESSED
+                let key = self.read_reg_vx(x);
+                self.skip_if(connector.is_key_pressed(key));
+            },
+            Instruction::SkipKeyNotPressed(x) => {
+                // SKIPS THE NEXT INSTRUCTION IF THE KEY STORED IN VX IS NOT PRESSED
+                let key = self.read_reg_vx(x);
+                self.skip_if(!connector.is_key_pressed(key));
             }
-            0xF => {
+            Instruction::LoadDelayTimer(x) => {
+                // SETS VX TO THE VALUE OF THE DELAY TIMER.
+                let temp = connector.get_delay_timer();
+                self.write_reg_vx(x, temp);
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::WaitKey(x) => {
+                // A KEY PRESSED IS AWAITED AND STORED IN VX.
+                if let Some(val) = connector.get_key_pressed() {
+                    self.write_reg_vx(x, val);
+                    self.pc = self.wrap_pc(self.pc, 2);
+                }
+            },
+            Instruction::SetDelayTimer(x) => {
+                // SETS THE DELAY TIMER TO VX.
+                connector.change_delay_timer(self.read_reg_vx(x));
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::SetSoundTimer(x) => {
+                // SETS THE SOUND TIMER TO VX.
+                connector.change_sound_timer(self.read_reg_vx(x));
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::AddI(x) => {
+                // ADDS VX TO I
                 let temp_x = self.read_reg_vx(x);
-                match nn {
-                    0x07 => {
-                        // SETS VX TO THE VALUE OF THE DELAY TIMER.
-                        let temp = connector.get_delay_timer();
-                        self.write_reg_vx(x, temp);
-                        self.pc += 2;
-                    },
-                    0x0A => {
-                        // A KEY PRESSED IS AWAITED AND STORED IN VX.
-                        let key = connector.get_key_pressed();
-                        match key {
-                            Some(val) => {
-                                self.write_reg_vx(x, val);
-                                self.pc += 2;
-                            },
-                            None => {
-                            }
-                        }
-                    },
-                    0x15 => {
-                        // SETS THE DELAY TIMER TO VX.
-                        connector.change_delay_timer(temp_x);
-                        self.pc += 2;
-                    },
-                    0x18 => {
-                        // SETS THE SOUND TIMER TO VX.
-                        connector.change_sound_timer(temp_x);
-                        self.pc += 2;
-                    },
-                    0x1E => {
-                        // ADDS VX TO I
-                        self.i += temp_x as u16;
-                        self.pc += 2;
-                    },
-                    0x29 => {
-                        // SETS I TO THE LOCATION OF THE SPRITE FOR THE CHARACTER IN VX.
-                        self.i = temp_x as u16 * 5;
-                        self.pc += 2;
-                    },
-                    0x33 => {
-                        // STORES THE BINARY-CODED DECIMAL REPRESENTATION OF VX, WITH THE HUNDREDS
-                        // DIGIT IN MEMORY AT LOCATION IN I, THE TENS DIGIT AT LOCATION I+1, AND THE
-                        // ONES DIGIT AT LOCATION I+2.
-                        connector.write_byte_ram(self.i, temp_x / 100);
-                        connector.write_byte_ram(self.i + 1, (temp_x % 100) / 10);
-                        connector.write_byte_ram(self.i + 2, temp_x % 10);
-                        self.pc += 2;
-                    },
-                    0x55 => {
-                        // STORES FROM V0 TO VX INCLUDED IN MEMORY, STARTING AT ADDRESS I.
-                        // THE OFFSET FROM I IS INCREASED BY 1 FOR EACH VALUE WRITTEN, BUT I
-                        // ITSELF IS UNMODIFIED.
-                        for j in 0..=x as usize {
-                            let value = self.read_reg_vx(j as u8);
-                            connector.write_byte_ram(self.i + j as u16, value);
-                        }
-                        self.pc += 2;
-                    },
-                    0x65 => {
-                        // FILLS FROM V0 TO VX INCLUDED WITH VALUES FROM MEMORY, STARTING AT
-                        // ADDRESS I. THE OFFSET FROM I IS INCREASED BY 1 FOR EACH VALUE READ, BUT
-                        // I ITSELF IS UNMODIFIED.
-                        for j in 0..=x as usize {
-                            let value = connector.read_byte_ram(self.i + j as u16);
-                            self.write_reg_vx(j as u8, value);
-                        }
-                        self.pc += 2;
-                    },
-                    _ => {
-                        panic!("Unknow instruction for 0xF.")
-                    }
+                let total = self.i as usize + temp_x as usize;
+                if self.quirks.fx1e_sets_vf {
+                    self.write_reg_vx(0xF, (total >= self.addressing.memory_size()) as u8);
                 }
-            }
-            _ =>  {
-                panic!("Unknown instruction {:#X}", instruction);
-            }
+                self.i = self.i.wrapping_add(temp_x as u16);
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::LoadFont(x) => {
+                // SETS I TO THE LOCATION OF THE SPRITE FOR THE CHARACTER IN VX.
+                self.i = self.read_reg_vx(x) as u16 * 5;
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::StoreBcd(x) => {
+                // STORES THE BINARY-CODED DECIMAL REPRESENTATION OF VX, WITH THE HUNDREDS
+                // DIGIT IN MEMORY AT LOCATION IN I, THE TENS DIGIT AT LOCATION I+1, AND THE
+                // ONES DIGIT AT LOCATION I+2.
+                let temp_x = self.read_reg_vx(x);
+                self.address(self.i, 2)?;
+                connector.write_byte_ram(self.address(self.i, 0)?, temp_x / 100)?;
+                connector.write_byte_ram(self.address(self.i, 1)?, (temp_x % 100) / 10)?;
+                connector.write_byte_ram(self.address(self.i, 2)?, temp_x % 10)?;
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::StoreRegisters(x) => {
+                // STORES FROM V0 TO VX INCLUDED IN MEMORY, STARTING AT ADDRESS I.
+                // THE OFFSET FROM I IS INCREASED BY 1 FOR EACH VALUE WRITTEN, BUT I
+                // ITSELF IS UNMODIFIED.
+                self.address(self.i, x as usize)?;
+                for j in 0..=x as usize {
+                    let value = self.read_reg_vx(j as u8);
+                    connector.write_byte_ram(self.address(self.i, j)?, value)?;
+                }
+                if self.quirks.memory_increments_i {
+                    self.i = self.i.wrapping_add(x as u16 + 1);
+                }
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::LoadRegisters(x) => {
+                // FILLS FROM V0 TO VX INCLUDED WITH VALUES FROM MEMORY, STARTING AT
+                // ADDRESS I. THE OFFSET FROM I IS INCREASED BY 1 FOR EACH VALUE READ, BUT
+                // I ITSELF IS UNMODIFIED.
+                self.address(self.i, x as usize)?;
+                for j in 0..=x as usize {
+                    let value = connector.read_byte_ram(self.address(self.i, j)?)?;
+                    self.write_reg_vx(j as u8, value);
+                }
+                if self.quirks.memory_increments_i {
+                    self.i = self.i.wrapping_add(x as u16 + 1);
+                }
+                self.pc = self.wrap_pc(self.pc, 2);
+            },
+            Instruction::Unknown(opcode) => {
+                return Err(Chip8Error::UnknownInstruction(opcode));
+            },
         }
+        Ok(())
     }
 
-    pub fn debug_draw_sprite(&mut self, connector: &mut Connector, x: u8, y: u8, height: u8) {
+    // PC MOVES OVER THE NEXT INSTRUCTION WHEN condition HOLDS, TO IT OTHERWISE
+    fn skip_if(&mut self, condition: bool) {
+        if condition {
+            self.pc = self.wrap_pc(self.pc, 4);
+        } else {
+            self.pc = self.wrap_pc(self.pc, 2);
+        }
+    }
+
+    // BASE + OFFSET AS A PC, WRAPPED AROUND LIKE ANY OTHER ADDRESS. WITH Trap IT IS LEFT PAST THE
+    // END OF MEMORY, THE NEXT FETCH FAILS THEN.
+    fn wrap_pc(&self, base: u16, offset: u16) -> u16 {
+        self.address(base, offset as usize).unwrap_or(base.wrapping_add(offset))
+    }
+
+    // 8XY1, 8XY2 AND 8XY3
+    fn write_logic_result(&mut self, x: u8, value: u8) {
+        self.write_reg_vx(x, value);
+        if self.quirks.vf_reset {
+            self.write_reg_vx(0xF, 0);
+        }
+        self.pc = self.wrap_pc(self.pc, 2);
+    }
+
+    // OPCODE AT PC, WITHOUT EXECUTING IT
+    pub fn peek_opcode(&self, connector: &Connector) -> Result<u16, Chip8Error> {
+        let high = connector.read_byte_ram(self.address(self.pc, 0)?)? as u16;
+        let low = connector.read_byte_ram(self.address(self.pc, 1)?)? as u16;
+        Ok((high << 8) | low)
+    }
+
+    fn push_stack(&mut self, connector: &mut Connector, address: u16) -> Result<(), Chip8Error> {
+        if self.sp >= self.stack.depth.min(MAX_STACK_DEPTH) {
+            return Err(Chip8Error::StackOverflow);
+        }
+        if self.stack.in_ram {
+            write_stack_slot(connector, self.sp, address)?;
+        }
+        self.ret_stack[self.sp] = address;
+        self.sp += 1;
+        Ok(())
+    }
+
+    fn pop_stack(&mut self, connector: &Connector) -> Result<u16, Chip8Error> {
+        if self.sp == 0 {
+            return Err(Chip8Error::StackUnderflow);
+        }
+        let mut address = self.ret_stack[self.sp - 1];
+        if self.stack.in_ram {
+            address = read_stack_slot(connector, self.sp - 1)?;
+        }
+        self.sp -= 1;
+        Ok(address)
+    }
+
+    // MAPS BASE + OFFSET TO A MEMORY ADDRESS ACCORDING TO THE ADDRESSING POLICY
+    fn address(&self, base: u16, offset: usize) -> Result<u16, Chip8Error> {
+        let address = base as usize + offset;
+        match self.addressing {
+            Addressing::Wrap12 => Ok((address & 0xFFF) as u16),
+            Addressing::Wrap16 => Ok((address & 0xFFFF) as u16),
+            Addressing::Trap if address < MEMORY_SIZE => Ok(address as u16),
+            Addressing::Trap => Err(Chip8Error::AddressOutOfRange(address)),
+        }
+    }
+
+    pub fn debug_draw_sprite(&mut self, connector: &mut Connector, x: u8, y: u8, height: u8) -> Result<(), Chip8Error> {
+        if height > 0 {
+            self.address(self.i, height as usize - 1)?;
+        }
         let mut should_set_vf = false;
+        // THE STARTING POSITION ALWAYS WRAPS, THE PIXELS PAST THE EDGES WRAP OR ARE CLIPPED
+        let start_x = x as usize % WIDTH_SCREEN;
+        let start_y = y as usize % HEIGHT_SCREEN;
         for sprite_y in 0..height {
-            let b = connector.read_byte_ram(self.i + sprite_y as u16);
-            if connector.debug_draw_byte(b, x, y + sprite_y) {
+            let mut coord_y = start_y + sprite_y as usize;
+            if coord_y >= HEIGHT_SCREEN {
+                if self.quirks.clip_sprites {
+                    break;
+                }
+                coord_y %= HEIGHT_SCREEN;
+            }
+            let b = connector.read_byte_ram(self.address(self.i, sprite_y as usize)?)?;
+            if connector.debug_draw_byte(b, start_x, coord_y, self.quirks.clip_sprites) {
                 should_set_vf = true;
             }
         }
@@ -313,6 +384,7 @@ impl Cpu {
         } else {
             self.write_reg_vx(0xF, 0);
         }
+        Ok(())
     }
 
     pub fn write_reg_vx(&mut self, x: u8, value: u8) {
@@ -322,4 +394,112 @@ impl Cpu {
     pub fn read_reg_vx(&mut self, x: u8) -> u8 {
         self.vx[x as usize]
     }
+
+    pub fn get_registers(&self) -> &[u8; 16] {
+        &self.vx
+    }
+
+    pub fn get_pc(&self) -> u16 {
+        self.pc
+    }
+
+    pub fn change_pc(&mut self, pc: u16) {
+        self.pc = pc;
+    }
+
+    pub fn get_i(&self) -> u16 {
+        self.i
+    }
+
+    pub fn change_i(&mut self, i: u16) {
+        self.i = i;
+    }
+
+    pub fn get_stack(&self) -> &[u16] {
+        &self.ret_stack[..self.sp]
+    }
+
+    pub fn change_stack(&mut self, connector: &mut Connector, stack: &[u16]) -> Result<(), Chip8Error> {
+        if stack.len() > self.stack.depth.min(MAX_STACK_DEPTH) {
+            return Err(Chip8Error::StackOverflow);
+        }
+        self.sp = 0;
+        for address in stack {
+            self.push_stack(connector, *address)?;
+        }
+        Ok(())
+    }
+
+    pub fn change_quirks(&mut self, quirks: Quirks) {
+        self.quirks = quirks;
+    }
+
+    pub fn change_addressing(&mut self, addressing: Addressing) {
+        self.addressing = addressing;
+    }
+
+    // A STACK ALREADY DEEPER THAN THE NEW DEPTH IS KEPT, THE NEXT CALL OVERFLOWS
+    pub fn change_stack_config(&mut self, connector: &mut Connector, stack: Stack) {
+        if stack.in_ram && !self.stack.in_ram {
+            for slot in 0..self.sp {
+                // THE WHOLE STACK AREA ENDS BELOW 0xF20, SO IT IS ALWAYS IN MEMORY
+                write_stack_slot(connector, slot, self.ret_stack[slot]).expect("the stack area is in memory");
+            }
+        }
+        self.stack = stack;
+    }
+
+    pub fn set_random_seed(&mut self, seed: u32) {
+        self.random.set_seed(seed);
+    }
+
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bytes(&self.vx);
+        writer.write_u16(self.pc);
+        writer.write_u16(self.i);
+        writer.write_u8(self.sp as u8);
+        // EVERY SLOT IS WRITTEN, THE UNUSED ONES AS 0, SO THE SIZE OF A STATE NEVER CHANGES
+        for slot in 0..MAX_STACK_DEPTH {
+            writer.write_u16(self.get_stack().get(slot).copied().unwrap_or(0));
+        }
+        writer.write_u32(self.random.get_state());
+    }
+
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        self.vx.copy_from_slice(reader.read_bytes(16)?);
+        self.pc = reader.read_u16()?;
+        self.i = reader.read_u16()?;
+        let depth = reader.read_u8()? as usize;
+        if depth > self.stack.depth.min(MAX_STACK_DEPTH) {
+            return Err(StateError::InvalidValue("stack depth"));
+        }
+        for slot in 0..MAX_STACK_DEPTH {
+            let address = reader.read_u16()?;
+            if slot < depth {
+                self.ret_stack[slot] = address;
+            }
+        }
+        self.sp = depth;
+        // XORSHIFT NEVER REACHES 0, SO NO SAVED STATE CONTAINS IT
+        let random = reader.read_u32()?;
+        if random == 0 {
+            return Err(StateError::InvalidValue("random state"));
+        }
+        self.random.set_seed(random);
+        Ok(())
+    }
+}
+
+fn write_stack_slot(connector: &mut Connector, slot: usize, address: u16) -> Result<(), Chip8Error> {
+    let slot_address = VIP_STACK_ADDRESS + 2 * slot as u16;
+    let [high, low] = address.to_be_bytes();
+    connector.write_byte_ram(slot_address, high)?;
+    connector.write_byte_ram(slot_address + 1, low)
+}
+
+fn read_stack_slot(connector: &Connector, slot: usize) -> Result<u16, Chip8Error> {
+    let slot_address = VIP_STACK_ADDRESS + 2 * slot as u16;
+    let high = connector.read_byte_ram(slot_address)?;
+    let low = connector.read_byte_ram(slot_address + 1)?;
+    Ok(u16::from_be_bytes([high, low]))
 }
@@ -0,0 +1,477 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use crate::chip8::{MEMORY_SIZE, PROGRAM_START};
+use crate::control_flow::ControlFlowGraph;
+use crate::disasm;
+
+// TURNS A ROM INTO OCTO SOURCE. LOOPS, IF / ELSE AND WHILE ARE ONLY RECOVERED WHERE OCTO
+// WOULD ASSEMBLE THEM INTO THE SAME BYTES, EVERYTHING ELSE STAYS A PLAIN STATEMENT, A JUMP
+// TO A LABEL OR RAW BYTES, SO THE SOURCE ASSEMBLES BACK INTO THE ROM:
+//   loop ... again                      ANY BACKWARD 1NNN
+//   if C begin ... else ... end         A SKIP WHEN C, THEN A FORWARD 1NNN OVER THE BODY
+//   while C                             THE SAME, JUMPING RIGHT AFTER THE again
+//   if C then STATEMENT                 ANY OTHER SKIP, WITH C INVERTED
+// CODE IS WHAT THE CONTROL-FLOW GRAPH REACHES, THE REST OF THE ROM IS DATA.
+
+pub fn decompile(rom: &[u8]) -> String {
+    Decompiler::new(rom).source()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Structure {
+    // loop AT start, again AT again
+    Loop { start: u16, again: u16 },
+    // if begin AT start (THE SKIP AND THE JUMP), else AT else_at WHEN THERE IS ONE, end AT end
+    If { start: u16, else_at: Option<u16>, end: u16 },
+}
+
+impl Structure {
+    fn start(self) -> u16 {
+        match self {
+            Structure::Loop { start, .. } | Structure::If { start, .. } => start,
+        }
+    }
+
+    // PAST ITS LAST BYTE
+    fn end(self) -> u16 {
+        match self {
+            Structure::Loop { again, .. } => again + 2,
+            Structure::If { end, .. } => end,
+        }
+    }
+
+    // WHERE OTHER STRUCTURES CAN NEST
+    fn bodies(self) -> Vec<(u16, u16)> {
+        match self {
+            Structure::Loop { start, again } => vec![(start, again)],
+            Structure::If { start, else_at: None, end } => vec![(start + 4, end)],
+            Structure::If { start, else_at: Some(else_at), end } => vec![(start + 4, else_at), (else_at + 2, end)],
+        }
+    }
+
+    fn contains(self, inner: Structure) -> bool {
+        self.bodies().iter().any(|(first, last)| *first <= inner.start() && inner.end() <= *last)
+    }
+
+    fn is_disjoint(self, other: Structure) -> bool {
+        self.end() <= other.start() || other.end() <= self.start()
+    }
+}
+
+// WHAT TO DO WITH THE BYTES AT AN ADDRESS
+enum Token {
+    // A SKIP AND ITS 1NNN, OPENING AN if begin
+    Begin(u16),
+    Else,
+    Again,
+    // A SKIP AND ITS 1NNN OUT OF THE LOOP
+    While(u16),
+    // A SKIP AND THE STATEMENT IT SKIPS
+    Then(u16, u16),
+    Statement(u16),
+}
+
+struct Decompiler<'a> {
+    rom: &'a [u8],
+    end: u16,
+    // THE INSTRUCTIONS WRITTEN AS CODE, BY ADDRESS
+    code: BTreeMap<u16, u16>,
+    labels: BTreeMap<u16, String>,
+    aliases: Vec<String>,
+    structures: Vec<Structure>,
+    whiles: BTreeSet<u16>,
+}
+
+impl<'a> Decompiler<'a> {
+    fn new(rom: &'a [u8]) -> Decompiler<'a> {
+        let graph = ControlFlowGraph::build(rom);
+        let end = PROGRAM_START + rom.len().min(MEMORY_SIZE - PROGRAM_START as usize) as u16;
+        let reached: BTreeMap<u16, u16> = graph.blocks.iter().flat_map(|block| block.instructions.iter().copied()).collect();
+
+        // AN INSTRUCTION OVERLAPPING THE ONE BEFORE STAYS IN ITS BYTES
+        let mut code = BTreeMap::new();
+        let mut address = PROGRAM_START;
+        while address < end {
+            match reached.get(&address) {
+                Some(opcode) if address + 2 <= end => {
+                    code.insert(address, *opcode);
+                    address += 2;
+                },
+                _ => address += 1,
+            }
+        }
+
+        let mut decompiler = Decompiler {
+            rom,
+            end,
+            code,
+            labels: BTreeMap::new(),
+            aliases: (0..16).map(|x| format!("v{:x}", x)).collect(),
+            structures: Vec::new(),
+            whiles: BTreeSet::new(),
+        };
+        decompiler.name_labels(&graph);
+        decompiler.name_registers();
+        decompiler.find_structures();
+        decompiler.drop_structure_labels();
+        decompiler
+    }
+
+    // ADDRESSES A LABEL CAN GO BEFORE: NOT INSIDE A 2 BYTE STATEMENT
+    fn is_boundary(&self, address: u16) -> bool {
+        (PROGRAM_START..=self.end).contains(&address) && !self.code.contains_key(&address.wrapping_sub(1))
+    }
+
+    fn name_labels(&mut self, graph: &ControlFlowGraph) {
+        let mut labels = BTreeMap::new();
+        // THE FIRST NAME GIVEN WINS: main, THEN SUBROUTINES, JUMP TARGETS AND DATA
+        labels.insert(PROGRAM_START, String::from("main"));
+        for subroutine in &graph.subroutines {
+            labels.entry(*subroutine).or_insert_with(|| format!("sub_{:04x}", subroutine));
+        }
+        for opcode in self.code.values() {
+            let target = opcode & 0x0FFF;
+            if matches!(opcode >> 12, 0x1 | 0xB) {
+                labels.entry(target).or_insert_with(|| format!("label_{:04x}", target));
+            }
+        }
+        for opcode in self.code.values() {
+            let target = opcode & 0x0FFF;
+            if opcode >> 12 == 0xA {
+                labels.entry(target).or_insert_with(|| format!("data_{:04x}", target));
+            }
+        }
+        labels.retain(|address, _| self.is_boundary(*address));
+        self.labels = labels;
+    }
+
+    // A REGISTER IS NAMED AFTER WHAT IT IS USED FOR THE MOST. V0, USED BY jump0, load AND save,
+    // AND VF, THE FLAG, KEEP THEIR NAMES.
+    fn name_registers(&mut self) {
+        const USES: [&str; 6] = ["timer", "keycode", "pos_x", "pos_y", "digit", "rnd"];
+        let mut counts = [[0usize; USES.len()]; 16];
+        for opcode in self.code.values() {
+            let x = ((opcode & 0x0F00) >> 8) as usize;
+            let y = ((opcode & 0x00F0) >> 4) as usize;
+            match (opcode >> 12, opcode & 0xFF) {
+                (0xF, 0x07 | 0x15) => counts[x][0] += 1,
+                (0xF, 0x0A) | (0xE, 0x9E | 0xA1) => counts[x][1] += 1,
+                (0xD, _) => {
+                    counts[x][2] += 1;
+                    counts[y][3] += 1;
+                },
+                (0xF, 0x29 | 0x33) => counts[x][4] += 1,
+                (0xC, _) => counts[x][5] += 1,
+                _ => {},
+            }
+        }
+
+        // THE MOST USED REGISTER GETS THE PLAIN NAME, THE NEXT ONES A NUMBER
+        let mut named: Vec<(usize, usize, usize)> = (1..15)
+            .filter_map(|x| {
+                let (use_index, count) = counts[x].iter().enumerate().max_by_key(|(index, count)| (**count, usize::MAX - index))?;
+                (*count > 0).then_some((x, use_index, *count))
+            })
+            .collect();
+        named.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+        let mut taken = [0; USES.len()];
+        for (x, use_index, _) in named {
+            taken[use_index] += 1;
+            self.aliases[x] = match taken[use_index] {
+                1 => USES[use_index].to_string(),
+                n => format!("{}{}", USES[use_index], n),
+            };
+        }
+    }
+
+    fn find_structures(&mut self) {
+        let mut candidates = Vec::new();
+        for (address, opcode) in &self.code {
+            let target = opcode & 0x0FFF;
+            // loop ... again: A JUMP BACK THAT NO SKIP NEEDS AS ITS then STATEMENT
+            let back = opcode >> 12 == 0x1 && target <= *address && target >= PROGRAM_START;
+            if back && self.is_boundary(target) && !self.follows_skip(*address) {
+                candidates.push(Structure::Loop { start: target, again: *address });
+            }
+            // if ... begin: A SKIP OVER A JUMP FORWARD, NOTHING JUMPING TO THE JUMP ITSELF
+            if let Some(end) = self.skipped_jump(*address) {
+                if end > address + 4 && end <= self.end && self.is_boundary(end) {
+                    // WITH else, THE BEGIN JUMP GOES TO THE ELSE BODY AND THE ELSE JUMP TO THE end
+                    if let Some(else_at) = self.else_jump(*address + 4, end) {
+                        let end = self.code[&else_at] & 0x0FFF;
+                        candidates.push(Structure::If { start: *address, else_at: Some(else_at), end });
+                    }
+                    candidates.push(Structure::If { start: *address, else_at: None, end });
+                }
+            }
+        }
+        // OUTER STRUCTURES FIRST, A LOOP AROUND AN if ENDING WITH IT INCLUDED. THE FIRST ONE
+        // THAT FITS WINS.
+        candidates.sort_by_key(|candidate| (candidate.start(), u16::MAX - candidate.end(), matches!(candidate, Structure::If { .. })));
+        candidates.dedup();
+
+        for candidate in candidates {
+            // while: THE JUMP LEAVES THE LOOP IT IS DIRECTLY IN
+            if let Structure::If { start, else_at: None, end } = candidate {
+                if let Some(Structure::Loop { again, .. }) = self.innermost(start) {
+                    if end == again + 2 && !self.whiles.contains(&start) {
+                        self.whiles.insert(start);
+                        continue;
+                    }
+                }
+            }
+            // AN if STARTING WHERE ANOTHER ONE STARTS USES THE SAME SKIP
+            let fits = !self.whiles.contains(&candidate.start()) && self.structures.iter().all(|accepted| {
+                let same_skip = matches!((candidate, accepted), (Structure::If { .. }, Structure::If { .. }))
+                    && candidate.start() == accepted.start();
+                !same_skip && (accepted.is_disjoint(candidate) || accepted.contains(candidate))
+            });
+            if fits {
+                self.structures.push(candidate);
+            }
+        }
+    }
+
+    // A LABEL ONLY THE JUMPS OF loop, if AND while GO TO IS NOT WRITTEN
+    fn drop_structure_labels(&mut self) {
+        let mut structural = BTreeSet::new();
+        for structure in &self.structures {
+            match *structure {
+                Structure::Loop { again, .. } => {
+                    structural.insert(again);
+                },
+                Structure::If { start, else_at, .. } => {
+                    structural.insert(start + 2);
+                    structural.extend(else_at);
+                },
+            }
+        }
+        structural.extend(self.whiles.iter().map(|start| start + 2));
+        let used: BTreeSet<u16> = self
+            .code
+            .iter()
+            .filter(|(address, opcode)| matches!(*opcode >> 12, 0x1 | 0x2 | 0xA | 0xB) && !structural.contains(address))
+            .map(|(_, opcode)| opcode & 0x0FFF)
+            .collect();
+        self.labels.retain(|address, _| *address == PROGRAM_START || used.contains(address));
+    }
+
+    // END OF if C begin ... end: THE TARGET OF THE FORWARD JUMP RIGHT AFTER A SKIP
+    fn skipped_jump(&self, address: u16) -> Option<u16> {
+        let opcode = self.code.get(&address)?;
+        let jump = self.code.get(&(address + 2))?;
+        let target = jump & 0x0FFF;
+        (is_octo_skip(*opcode) && jump >> 12 == 0x1 && target > address + 2 && !self.labels.contains_key(&(address + 2))).then_some(target)
+    }
+
+    // else: A FORWARD JUMP ENDING THE BODY, OVER THE ELSE BODY
+    fn else_jump(&self, body: u16, end: u16) -> Option<u16> {
+        let else_at = end.checked_sub(2).filter(|else_at| *else_at >= body)?;
+        let jump = self.code.get(&else_at)?;
+        let target = jump & 0x0FFF;
+        let fits = target > end && target <= self.end && self.is_boundary(target);
+        (jump >> 12 == 0x1 && fits && !self.follows_skip(else_at)).then_some(else_at)
+    }
+
+    fn follows_skip(&self, address: u16) -> bool {
+        self.code.get(&address.wrapping_sub(2)).is_some_and(|opcode| is_octo_skip(*opcode))
+    }
+
+    fn innermost(&self, address: u16) -> Option<Structure> {
+        self.structures
+            .iter()
+            .filter(|structure| structure.bodies().iter().any(|(first, last)| (*first..*last).contains(&address)))
+            .max_by_key(|structure| structure.start())
+            .copied()
+    }
+
+    fn token(&self, address: u16) -> Option<Token> {
+        let opcode = *self.code.get(&address)?;
+        for structure in &self.structures {
+            match *structure {
+                Structure::If { start, .. } if start == address => return Some(Token::Begin(opcode)),
+                Structure::If { else_at: Some(else_at), .. } if else_at == address => return Some(Token::Else),
+                Structure::Loop { again, .. } if again == address => return Some(Token::Again),
+                _ => {},
+            }
+        }
+        if self.whiles.contains(&address) {
+            return Some(Token::While(opcode));
+        }
+        if is_octo_skip(opcode) {
+            // if C then NEEDS A PLAIN STATEMENT RIGHT AFTER, WITH NOTHING IN BETWEEN
+            let next = address + 2;
+            let plain = self.code.get(&next).filter(|next_opcode| {
+                !is_octo_skip(**next_opcode)
+                    && !self.labels.contains_key(&next)
+                    && self.statement(**next_opcode).is_some()
+                    && matches!(self.token(next), Some(Token::Statement(_)))
+                    && self.structures.iter().all(|structure| structure.start() != next && structure.end() != next)
+            });
+            if let Some(next_opcode) = plain {
+                return Some(Token::Then(opcode, *next_opcode));
+            }
+        }
+        Some(Token::Statement(opcode))
+    }
+
+    fn source(&self) -> String {
+        let mut source = format!("# DECOMPILED FROM A {} BYTE ROM\n", self.rom.len());
+        for (x, alias) in self.aliases.iter().enumerate() {
+            if *alias != format!("v{:x}", x) {
+                let _ = writeln!(source, ":alias {} v{:x}", alias, x);
+            }
+        }
+
+        let mut depth = 0;
+        let mut data: Vec<u8> = Vec::new();
+        let mut address = PROGRAM_START;
+        loop {
+            let closing = self.structures.iter().filter(|structure| matches!(structure, Structure::If { end, .. } if *end == address)).count();
+            let opening = self.structures.iter().filter(|structure| matches!(structure, Structure::Loop { start, .. } if *start == address)).count();
+            let label = self.labels.get(&address);
+            let token = self.token(address);
+            if closing > 0 || opening > 0 || label.is_some() || token.is_some() || data.len() == 8 || address >= self.end {
+                flush_data(&mut source, &mut data, depth);
+            }
+            for _ in 0..closing {
+                depth -= 1;
+                line(&mut source, depth, "end");
+            }
+            if let Some(label) = label {
+                let _ = writeln!(source, "\n: {}", label);
+            }
+            if address >= self.end {
+                break;
+            }
+            for _ in 0..opening {
+                line(&mut source, depth, "loop");
+                depth += 1;
+            }
+
+            let Some(token) = token else {
+                data.push(self.rom[(address - PROGRAM_START) as usize]);
+                address += 1;
+                continue;
+            };
+            match token {
+                Token::Begin(opcode) => {
+                    line(&mut source, depth, &format!("if {} begin", self.condition(opcode, false)));
+                    depth += 1;
+                    address += 4;
+                },
+                Token::Else => {
+                    line(&mut source, depth - 1, "else");
+                    address += 2;
+                },
+                Token::Again => {
+                    depth -= 1;
+                    line(&mut source, depth, "again");
+                    address += 2;
+                },
+                Token::While(opcode) => {
+                    line(&mut source, depth, &format!("while {}", self.condition(opcode, false)));
+                    address += 4;
+                },
+                Token::Then(opcode, next_opcode) => {
+                    let statement = self.statement(next_opcode).unwrap_or_default();
+                    line(&mut source, depth, &format!("if {} then {}", self.condition(opcode, true), statement));
+                    address += 4;
+                },
+                Token::Statement(opcode) => {
+                    let statement = self.statement(opcode).unwrap_or_else(|| {
+                        format!("0x{:02X} 0x{:02X} # {}", opcode >> 8, opcode & 0xFF, disasm::disassemble(opcode))
+                    });
+                    line(&mut source, depth, &statement);
+                    address += 2;
+                },
+            }
+        }
+        source
+    }
+
+    // WHEN THE SKIP DOES NOT HAPPEN, OR WHEN IT DOES WITH inverted
+    fn condition(&self, opcode: u16, inverted: bool) -> String {
+        let vx = &self.aliases[((opcode & 0x0F00) >> 8) as usize];
+        let vy = &self.aliases[((opcode & 0x00F0) >> 4) as usize];
+        let nn = opcode & 0xFF;
+        let (skip, stay) = match (opcode >> 12, nn) {
+            (0x3, _) => (format!("{} == 0x{:02X}", vx, nn), format!("{} != 0x{:02X}", vx, nn)),
+            (0x4, _) => (format!("{} != 0x{:02X}", vx, nn), format!("{} == 0x{:02X}", vx, nn)),
+            (0x5, _) => (format!("{} == {}", vx, vy), format!("{} != {}", vx, vy)),
+            (0x9, _) => (format!("{} != {}", vx, vy), format!("{} == {}", vx, vy)),
+            (0xE, 0x9E) => (format!("{} key", vx), format!("{} -key", vx)),
+            _ => (format!("{} -key", vx), format!("{} key", vx)),
+        };
+        if inverted { stay } else { skip }
+    }
+
+    // THE OCTO STATEMENT ASSEMBLING TO opcode, NONE WHEN THERE IS NO SUCH STATEMENT
+    fn statement(&self, opcode: u16) -> Option<String> {
+        let x = ((opcode & 0x0F00) >> 8) as usize;
+        let vx = &self.aliases[x];
+        let vy = &self.aliases[((opcode & 0x00F0) >> 4) as usize];
+        let n = opcode & 0xF;
+        let nn = opcode & 0xFF;
+        let target = self.address_name(opcode & 0x0FFF);
+        let statement = match (opcode >> 12, nn, n) {
+            _ if opcode == 0x00E0 => String::from("clear"),
+            _ if opcode == 0x00EE => String::from("return"),
+            (0x1, _, _) => format!("jump {}", target),
+            (0x2, _, _) if self.labels.contains_key(&(opcode & 0x0FFF)) => target,
+            (0x2, _, _) => format!(":call {}", target),
+            (0x6, _, _) => format!("{} := 0x{:02X}", vx, nn),
+            (0x7, _, _) => format!("{} += 0x{:02X}", vx, nn),
+            (0x8, _, 0x0) => format!("{} := {}", vx, vy),
+            (0x8, _, 0x1) => format!("{} |= {}", vx, vy),
+            (0x8, _, 0x2) => format!("{} &= {}", vx, vy),
+            (0x8, _, 0x3) => format!("{} ^= {}", vx, vy),
+            (0x8, _, 0x4) => format!("{} += {}", vx, vy),
+            (0x8, _, 0x5) => format!("{} -= {}", vx, vy),
+            (0x8, _, 0x6) => format!("{} >>= {}", vx, vy),
+            (0x8, _, 0x7) => format!("{} =- {}", vx, vy),
+            (0x8, _, 0xE) => format!("{} <<= {}", vx, vy),
+            (0xA, _, _) => format!("i := {}", target),
+            (0xB, _, _) => format!("jump0 {}", target),
+            (0xC, _, _) => format!("{} := random 0x{:02X}", vx, nn),
+            (0xD, _, _) => format!("sprite {} {} {}", vx, vy, n),
+            (0xF, 0x07, _) => format!("{} := delay", vx),
+            (0xF, 0x0A, _) => format!("{} := key", vx),
+            (0xF, 0x15, _) => format!("delay := {}", vx),
+            (0xF, 0x18, _) => format!("buzzer := {}", vx),
+            (0xF, 0x1E, _) => format!("i += {}", vx),
+            (0xF, 0x29, _) => format!("i := hex {}", vx),
+            (0xF, 0x33, _) => format!("bcd {}", vx),
+            // save AND load TAKE THE LAST REGISTER OF V0 TO VX, ITS NAME WOULD MISLEAD
+            (0xF, 0x55, _) => format!("save v{:x}", x),
+            (0xF, 0x65, _) => format!("load v{:x}", x),
+            _ => return None,
+        };
+        Some(statement)
+    }
+
+    fn address_name(&self, address: u16) -> String {
+        match self.labels.get(&address) {
+            Some(label) => label.clone(),
+            None => format!("0x{:03X}", address),
+        }
+    }
+}
+
+// THE SKIPS OCTO CAN WRITE: 5XYN AND 9XYN ONLY WITH N = 0
+fn is_octo_skip(opcode: u16) -> bool {
+    disasm::is_skip(opcode) && (!matches!(opcode >> 12, 0x5 | 0x9) || opcode & 0xF == 0)
+}
+
+fn line(source: &mut String, depth: usize, text: &str) {
+    let _ = writeln!(source, "{}{}", "\t".repeat(depth + 1), text);
+}
+
+fn flush_data(source: &mut String, data: &mut Vec<u8>, depth: usize) {
+    if data.is_empty() {
+        return;
+    }
+    let bytes: Vec<String> = data.iter().map(|byte| format!("0x{:02X}", byte)).collect();
+    line(source, depth, &bytes.join(" "));
+    data.clear();
+}
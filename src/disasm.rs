@@ -0,0 +1,76 @@
+// CHIP-8 MNEMONICS IN THE USUAL "CLS", "LD V1, 0x03", "DRW V0, V1, 5" SYNTAX, DECODED THE
+// WAY THE CPU DECODES THEM. WORDS THAT ARE NOT INSTRUCTIONS COME OUT AS "DW 0x1234".
+
+use crate::instruction::Instruction;
+
+pub fn disassemble(opcode: u16) -> String {
+    let x = (opcode & 0x0F00) >> 8;
+    let y = (opcode & 0x00F0) >> 4;
+    let n = opcode & 0x000F;
+    let nn = opcode & 0x00FF;
+    let nnn = opcode & 0x0FFF;
+
+    match (opcode >> 12, nn, n) {
+        (0x0, 0xE0, _) => String::from("CLS"),
+        (0x0, 0xEE, _) => String::from("RET"),
+        (0x0, _, _) => format!("SYS 0x{:03X}", nnn),
+        (0x1, _, _) => format!("JP 0x{:03X}", nnn),
+        (0x2, _, _) => format!("CALL 0x{:03X}", nnn),
+        (0x3, _, _) => format!("SE V{:X}, 0x{:02X}", x, nn),
+        (0x4, _, _) => format!("SNE V{:X}, 0x{:02X}", x, nn),
+        (0x5, _, _) => format!("SE V{:X}, V{:X}", x, y),
+        (0x6, _, _) => format!("LD V{:X}, 0x{:02X}", x, nn),
+        (0x7, _, _) => format!("ADD V{:X}, 0x{:02X}", x, nn),
+        (0x8, _, 0x0) => format!("LD V{:X}, V{:X}", x, y),
+        (0x8, _, 0x1) => format!("OR V{:X}, V{:X}", x, y),
+        (0x8, _, 0x2) => format!("AND V{:X}, V{:X}", x, y),
+        (0x8, _, 0x3) => format!("XOR V{:X}, V{:X}", x, y),
+        (0x8, _, 0x4) => format!("ADD V{:X}, V{:X}", x, y),
+        (0x8, _, 0x5) => format!("SUB V{:X}, V{:X}", x, y),
+        (0x8, _, 0x6) => format!("SHR V{:X}, V{:X}", x, y),
+        (0x8, _, 0x7) => format!("SUBN V{:X}, V{:X}", x, y),
+        (0x8, _, 0xE) => format!("SHL V{:X}, V{:X}", x, y),
+        (0x9, _, _) => format!("SNE V{:X}, V{:X}", x, y),
+        (0xA, _, _) => format!("LD I, 0x{:03X}", nnn),
+        (0xB, _, _) => format!("JP V0, 0x{:03X}", nnn),
+        (0xC, _, _) => format!("RND V{:X}, 0x{:02X}", x, nn),
+        (0xD, _, _) => format!("DRW V{:X}, V{:X}, {}", x, y, n),
+        (0xE, 0x9E, _) => format!("SKP V{:X}", x),
+        (0xE, 0xA1, _) => format!("SKNP V{:X}", x),
+        (0xF, 0x07, _) => format!("LD V{:X}, DT", x),
+        (0xF, 0x0A, _) => format!("LD V{:X}, K", x),
+        (0xF, 0x15, _) => format!("LD DT, V{:X}", x),
+        (0xF, 0x18, _) => format!("LD ST, V{:X}", x),
+        (0xF, 0x1E, _) => format!("ADD I, V{:X}", x),
+        (0xF, 0x29, _) => format!("LD F, V{:X}", x),
+        (0xF, 0x33, _) => format!("LD B, V{:X}", x),
+        (0xF, 0x55, _) => format!("LD [I], V{:X}", x),
+        (0xF, 0x65, _) => format!("LD V{:X}, [I]", x),
+        _ => format!("DW 0x{:04X}", opcode),
+    }
+}
+
+// THE OPCODE AT address, WRAPPING AT THE END OF MEMORY LIKE FETCH DOES
+pub fn opcode_at(memory: &[u8], address: usize) -> u16 {
+    let high = memory[address % memory.len()] as u16;
+    let low = memory[(address + 1) % memory.len()] as u16;
+    (high << 8) | low
+}
+
+// WHETHER THE CPU RUNS opcode INSTEAD OF STOPPING WITH UnknownInstruction
+pub fn is_instruction(opcode: u16) -> bool {
+    !matches!(Instruction::decode(opcode), Instruction::Unknown(_))
+}
+
+// WHETHER opcode SKIPS THE NEXT INSTRUCTION WHEN ITS CONDITION HOLDS
+pub fn is_skip(opcode: u16) -> bool {
+    matches!(
+        Instruction::decode(opcode),
+        Instruction::SkipEqual(..)
+            | Instruction::SkipNotEqual(..)
+            | Instruction::SkipEqualRegisters(..)
+            | Instruction::SkipNotEqualRegisters(..)
+            | Instruction::SkipKeyPressed(_)
+            | Instruction::SkipKeyNotPressed(_)
+    )
+}
@@ -1,40 +1,57 @@
+use crate::state::{StateError, StateReader, StateWriter};
+
 // ORIGINAL SIZE
 pub const WIDTH_SCREEN: usize = 64;
 pub const HEIGHT_SCREEN: usize = 32;
 
 pub struct Display {
     screen: [u8; WIDTH_SCREEN * HEIGHT_SCREEN],
+    // ONE BIT PER ROW, SET WHEN THE ROW CHANGED SINCE THE LAST PRESENT
+    dirty_rows: u32,
+}
+
+impl Default for Display {
+    fn default() -> Display {
+        Display::new()
+    }
 }
 
 impl Display {
     pub fn new() -> Display {
         Display {
             screen: [0; WIDTH_SCREEN * HEIGHT_SCREEN],
+            dirty_rows: 0,
         }
     }
 
     pub fn get_position_from_coords(x: usize, y: usize) -> usize {
         y * WIDTH_SCREEN + x
     }
-    pub fn debug_draw_sprite(&mut self, b: u8, x: u8, y: u8) -> bool {
+    pub fn debug_draw_sprite(&mut self, b: u8, x: usize, y: usize, clip: bool) -> bool {
         let mut collision = false;
-        let mut coord_x = x as usize;
-        let mut coord_y = y as usize;
+        let mut coord_x = x;
+        let mut coord_y = y;
         let mut byte = b;
 
         for _ in 0..8 {
+            if clip && coord_x >= WIDTH_SCREEN {
+                break;
+            }
             coord_x %= WIDTH_SCREEN;
             coord_y %= HEIGHT_SCREEN;
             let position = Display::get_position_from_coords(coord_x, coord_y);
             let bit = (byte & 0b1000_0000) >> 7;
             let prev_value = self.screen[position];
             self.screen[position] ^= bit;
+            if bit == 1 {
+                self.dirty_rows |= 1 << coord_y;
+            }
 
             if prev_value == 1 && self.screen[position] == 0 {
                 collision = true;
             }
             coord_x += 1;
-            byte = byte << 1;
+            byte <<= 1;
         }
         collision
     }
@@ -43,10 +60,38 @@ impl Display {
         for i in &mut self.screen {
             *i = 0;
         }
+        self.dirty_rows = u32::MAX;
     }
 
     pub fn get_display(&self) -> &[u8] {
         &self.screen
     }
 
+    pub fn is_dirty(&self) -> bool {
+        self.dirty_rows != 0
+    }
+
+    // ROWS PAST THE BOTTOM OF THE SCREEN ARE NEVER DIRTY
+    pub fn is_row_dirty(&self, y: usize) -> bool {
+        y < HEIGHT_SCREEN && self.dirty_rows & (1 << y) != 0
+    }
+
+    pub fn clear_dirty(&mut self) {
+        self.dirty_rows = 0;
+    }
+
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bytes(&self.screen);
+    }
+
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        let screen = reader.read_bytes(self.screen.len())?;
+        if screen.iter().any(|pixel| *pixel > 1) {
+            return Err(StateError::InvalidValue("display"));
+        }
+        self.screen.copy_from_slice(screen);
+        // THE WHOLE SCREEN HAS TO BE PRESENTED AGAIN
+        self.dirty_rows = u32::MAX;
+        Ok(())
+    }
 }
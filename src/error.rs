@@ -0,0 +1,28 @@
+use std::fmt;
+
+// ERRORS RAISED WHILE RUNNING A PROGRAM. THE FAULTING INSTRUCTION IS NOT EXECUTED AND PC
+// STILL POINTS AT IT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Chip8Error {
+    UnknownInstruction(u16),
+    StackOverflow,
+    StackUnderflow,
+    AddressOutOfRange(usize),
+    RomTooLarge(usize),
+}
+
+impl fmt::Display for Chip8Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Chip8Error::UnknownInstruction(opcode) => write!(f, "unknown instruction {:04X}", opcode),
+            Chip8Error::StackOverflow => write!(f, "too many nested subroutines"),
+            Chip8Error::StackUnderflow => write!(f, "return without a subroutine call"),
+            Chip8Error::AddressOutOfRange(address) => {
+                write!(f, "address {:#X} is out of memory", address)
+            }
+            Chip8Error::RomTooLarge(size) => write!(f, "rom of {} bytes does not fit in memory", size),
+        }
+    }
+}
+
+impl std::error::Error for Chip8Error {}
@@ -0,0 +1,42 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+// HOW OFTEN THE ROM FILE IS CHECKED FOR CHANGES
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// POLLS THE MODIFICATION TIME OF THE ROM FILE, NO FILE WATCHING SERVICE NEEDED
+pub struct Watcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_poll: Instant,
+}
+
+impl Watcher {
+    pub fn new(path: &Path) -> Watcher {
+        Watcher {
+            path: path.to_path_buf(),
+            modified: modified(path),
+            last_poll: Instant::now(),
+        }
+    }
+
+    // THE NEW CONTENT OF THE FILE WHEN IT CHANGED SINCE THE LAST CALL
+    pub fn poll(&mut self) -> Option<Vec<u8>> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+        let modified = modified(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        // A FILE STILL BEING WRITTEN IS PICKED UP AGAIN ON THE NEXT CHANGE
+        fs::read(&self.path).ok()
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
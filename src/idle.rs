@@ -0,0 +1,75 @@
+use crate::connector::Connector;
+use crate::cpu::Cpu;
+use crate::timing::{self, Executed};
+
+// A LOOP THAT CANNOT END BEFORE THE NEXT TIMER TICK OR KEY EVENT. BOTH ONLY HAPPEN BETWEEN
+// FRAMES, SO ALL THE WHOLE ITERATIONS LEFT IN THE FRAME CAN BE SKIPPED:
+//   FX0A WITH NO KEY PRESSED, WHICH RUNS AGAIN WITHOUT MOVING PC
+//   FX07 / 3XNN OR 4XNN / 1NNN BACK TO THE FX07, WHILE THE DELAY TIMER KEEPS THE SKIP FROM
+//   HAPPENING
+pub struct IdleLoop {
+    // INSTRUCTIONS IN ONE ITERATION
+    pub instructions: usize,
+    // VIP MACHINE CYCLES OF ONE ITERATION
+    pub cycles: u32,
+    // REGISTER FX07 LOADS WITH THE DELAY TIMER
+    timer_register: Option<u8>,
+}
+
+impl IdleLoop {
+    // THE LOOP STARTING AT PC, WHEN THE NEXT INSTRUCTION STARTS ONE
+    pub fn detect(cpu: &Cpu, connector: &Connector) -> Option<IdleLoop> {
+        let pc = cpu.get_pc();
+        let memory = connector.get_memory();
+        let read = |offset: usize| -> Option<u16> {
+            let address = pc as usize + offset;
+            Some(u16::from_be_bytes([*memory.get(address)?, *memory.get(address + 1)?]))
+        };
+        let opcode = read(0)?;
+        let x = ((opcode & 0x0F00) >> 8) as u8;
+        let price = |opcode: u16| {
+            timing::vip_cycles(&Executed {
+                opcode,
+                vx: 0,
+                v0: 0,
+                skipped: false,
+            })
+        };
+
+        match opcode & 0xF0FF {
+            0xF00A if connector.get_key_pressed().is_none() => Some(IdleLoop {
+                instructions: 1,
+                cycles: price(opcode),
+                timer_register: None,
+            }),
+            0xF007 => {
+                let skip = read(2)?;
+                let jump = read(4)?;
+                // 1NNN ONLY REACHES THE FIRST 4 KB
+                if pc > 0x0FFF || skip & 0x0F00 != opcode & 0x0F00 || jump != 0x1000 | pc {
+                    return None;
+                }
+                let timer = connector.get_delay_timer();
+                let nn = (skip & 0xFF) as u8;
+                let loops = match skip >> 12 {
+                    0x3 => timer != nn,
+                    0x4 => timer == nn,
+                    _ => false,
+                };
+                loops.then(|| IdleLoop {
+                    instructions: 3,
+                    cycles: price(opcode) + price(skip) + price(jump),
+                    timer_register: Some(x),
+                })
+            },
+            _ => None,
+        }
+    }
+
+    // LEAVES THE CPU AS RUNNING ONE OR MORE ITERATIONS WOULD: BACK AT THE START OF THE LOOP
+    pub fn skip(&self, cpu: &mut Cpu, connector: &Connector) {
+        if let Some(x) = self.timer_register {
+            cpu.write_reg_vx(x, connector.get_delay_timer());
+        }
+    }
+}
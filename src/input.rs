@@ -1,27 +1,40 @@
 pub struct Input {
-    key_pressed: Option<u8>,
+    // ONE BIT PER KEY, BIT 0 IS KEY 0 AND BIT 15 IS KEY F
+    keys_pressed: u16,
 }
 
 impl Input {
     pub fn new() -> Input {
         Input {
-            key_pressed: None
+            keys_pressed: 0
         }
     }
 
     pub fn is_key_pressed(&self, key_tested: u8) -> bool {
-        if let Some(key) = self.key_pressed {
-            key == key_tested
-        } else {
-            false
-        }
+        key_tested < 16 && self.keys_pressed & (1 << key_tested) != 0
     }
 
     pub fn change_key_pressed(&mut self, key: Option<u8>) {
-        self.key_pressed = key;
+        self.keys_pressed = match key {
+            Some(key) => 1 << (key & 0xF),
+            None => 0,
+        };
+    }
+
+    pub fn change_keys_pressed(&mut self, mask: u16) {
+        self.keys_pressed = mask;
     }
 
+    pub fn get_keys_pressed(&self) -> u16 {
+        self.keys_pressed
+    }
+
+    // LOWEST KEY CURRENTLY PRESSED, IF ANY
     pub fn get_key_pressed(&self) -> Option<u8> {
-        self.key_pressed
+        if self.keys_pressed == 0 {
+            None
+        } else {
+            Some(self.keys_pressed.trailing_zeros() as u8)
+        }
     }
 }
@@ -0,0 +1,124 @@
+// AN OPCODE WITH ITS OPERANDS ALREADY EXTRACTED, SO RUNNING IT AGAIN SKIPS THE DECODING.
+// THE QUIRKS ARE LEFT TO Cpu::execute, A DECODED INSTRUCTION STAYS VALID WHEN THEY CHANGE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Instruction {
+    // 00E0
+    ClearScreen,
+    // 00EE
+    Return,
+    // 1NNN
+    Jump(u16),
+    // 2NNN
+    Call(u16),
+    // 3XNN
+    SkipEqual(u8, u8),
+    // 4XNN
+    SkipNotEqual(u8, u8),
+    // 5XY0
+    SkipEqualRegisters(u8, u8),
+    // 6XNN
+    Load(u8, u8),
+    // 7XNN
+    Add(u8, u8),
+    // 8XY0
+    Move(u8, u8),
+    // 8XY1
+    Or(u8, u8),
+    // 8XY2
+    And(u8, u8),
+    // 8XY3
+    Xor(u8, u8),
+    // 8XY4
+    AddRegisters(u8, u8),
+    // 8XY5
+    Sub(u8, u8),
+    // 8XY6
+    ShiftRight(u8, u8),
+    // 8XY7
+    SubReverse(u8, u8),
+    // 8XYE
+    ShiftLeft(u8, u8),
+    // 9XY0
+    SkipNotEqualRegisters(u8, u8),
+    // ANNN
+    LoadI(u16),
+    // BNNN, X IS ONLY USED WITH THE jump_uses_vx QUIRK
+    JumpOffset(u8, u16),
+    // CXNN
+    Random(u8, u8),
+    // DXYN
+    Draw(u8, u8, u8),
+    // EX9E
+    SkipKeyPressed(u8),
+    // EXA1
+    SkipKeyNotPressed(u8),
+    // FX07
+    LoadDelayTimer(u8),
+    // FX0A
+    WaitKey(u8),
+    // FX15
+    SetDelayTimer(u8),
+    // FX18
+    SetSoundTimer(u8),
+    // FX1E
+    AddI(u8),
+    // FX29
+    LoadFont(u8),
+    // FX33
+    StoreBcd(u8),
+    // FX55
+    StoreRegisters(u8),
+    // FX65
+    LoadRegisters(u8),
+    // ANYTHING ELSE, RUNNING IT FAILS WITH UnknownInstruction
+    Unknown(u16),
+}
+
+impl Instruction {
+    // DECODES THE WAY THE CPU ALWAYS DID: 5XYN AND 9XYN IGNORE N, 0XE0 AND 0XEE IGNORE X
+    pub(crate) fn decode(opcode: u16) -> Instruction {
+        let nnn = opcode & 0x0FFF;
+        let nn = (opcode & 0x0FF) as u8;
+        let n = (opcode & 0x00F) as u8;
+        let x = ((opcode & 0x0F00) >> 8) as u8;
+        let y = ((opcode & 0x00F0) >> 4) as u8;
+
+        match ((opcode & 0xF000) >> 12, nn, n) {
+            (0x0, 0xEE, _) => Instruction::Return,
+            (0x0, 0xE0, _) => Instruction::ClearScreen,
+            (0x1, _, _) => Instruction::Jump(nnn),
+            (0x2, _, _) => Instruction::Call(nnn),
+            (0x3, _, _) => Instruction::SkipEqual(x, nn),
+            (0x4, _, _) => Instruction::SkipNotEqual(x, nn),
+            (0x5, _, _) => Instruction::SkipEqualRegisters(x, y),
+            (0x6, _, _) => Instruction::Load(x, nn),
+            (0x7, _, _) => Instruction::Add(x, nn),
+            (0x8, _, 0x0) => Instruction::Move(x, y),
+            (0x8, _, 0x1) => Instruction::Or(x, y),
+            (0x8, _, 0x2) => Instruction::And(x, y),
+            (0x8, _, 0x3) => Instruction::Xor(x, y),
+            (0x8, _, 0x4) => Instruction::AddRegisters(x, y),
+            (0x8, _, 0x5) => Instruction::Sub(x, y),
+            (0x8, _, 0x6) => Instruction::ShiftRight(x, y),
+            (0x8, _, 0x7) => Instruction::SubReverse(x, y),
+            (0x8, _, 0xE) => Instruction::ShiftLeft(x, y),
+            (0x9, _, _) => Instruction::SkipNotEqualRegisters(x, y),
+            (0xA, _, _) => Instruction::LoadI(nnn),
+            (0xB, _, _) => Instruction::JumpOffset(x, nnn),
+            (0xC, _, _) => Instruction::Random(x, nn),
+            (0xD, _, _) => Instruction::Draw(x, y, n),
+            (0xE, 0x9E, _) => Instruction::SkipKeyPressed(x),
+            (0xE, 0xA1, _) => Instruction::SkipKeyNotPressed(x),
+            (0xF, 0x07, _) => Instruction::LoadDelayTimer(x),
+            (0xF, 0x0A, _) => Instruction::WaitKey(x),
+            (0xF, 0x15, _) => Instruction::SetDelayTimer(x),
+            (0xF, 0x18, _) => Instruction::SetSoundTimer(x),
+            (0xF, 0x1E, _) => Instruction::AddI(x),
+            (0xF, 0x29, _) => Instruction::LoadFont(x),
+            (0xF, 0x33, _) => Instruction::StoreBcd(x),
+            (0xF, 0x55, _) => Instruction::StoreRegisters(x),
+            (0xF, 0x65, _) => Instruction::LoadRegisters(x),
+            _ => Instruction::Unknown(opcode),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use chip8_emulator::chip8::PROGRAM_START;
+use chip8_emulator::romdb::{self, Platform};
+use chip8_emulator::Addressing;
+use minifb::{Key, KeyRepeat, Window};
+use crate::osd::{Canvas, ADVANCE, BOX_COLOR, TEXT_COLOR, TEXT_HEIGHT};
+use crate::{HEIGHT, WIDTH};
+
+// ONE LINE OF TEXT PLUS SOME SPACING
+const LINE_HEIGHT: usize = TEXT_HEIGHT + 4;
+const MARGIN: usize = 8;
+// THE LIST FILLS THE WINDOW BUT THE HEADER LINE AND THE THREE LINES OF DETAILS
+const VISIBLE_ROWS: usize = (HEIGHT - 2 * MARGIN) / LINE_HEIGHT - 4;
+const COLUMNS: usize = (WIDTH - 2 * MARGIN) / ADVANCE;
+const DIM_COLOR: u32 = 0x808080;
+
+struct Entry {
+    path: PathBuf,
+    name: String,
+    size: usize,
+    platform: Platform,
+    title: Option<&'static str>,
+    description: &'static str,
+}
+
+// LISTS THE ROMS OF A DIRECTORY AND LETS THE PLAYER PICK ONE WITH THE ARROWS AND ENTER
+pub struct Launcher {
+    directory: PathBuf,
+    entries: Vec<Entry>,
+    selected: usize,
+    // FIRST ENTRY SHOWN, THE LIST SCROLLS TO KEEP THE SELECTION VISIBLE
+    scroll: usize,
+}
+
+impl Launcher {
+    pub fn new(directory: &Path) -> io::Result<Launcher> {
+        let mut entries = Vec::new();
+        for dir_entry in fs::read_dir(directory)? {
+            let Ok(dir_entry) = dir_entry else {
+                continue;
+            };
+            let path = dir_entry.path();
+            if !path.is_file() {
+                continue;
+            }
+            let Some(data) = read_rom(&path) else {
+                continue;
+            };
+            let info = romdb::lookup(&data);
+            entries.push(Entry {
+                name: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                size: data.len(),
+                platform: info.map_or_else(|| romdb::detect_platform(&data), |info| info.platform),
+                title: info.map(|info| info.title),
+                description: info.map_or("Not in the ROM database.", |info| info.description),
+                path,
+            });
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Launcher {
+            directory: directory.to_path_buf(),
+            entries,
+            selected: 0,
+            scroll: 0,
+        })
+    }
+
+    // BLOCKS UNTIL A ROM IS PICKED, OR RETURNS None WHEN THE PLAYER QUITS
+    pub fn pick(&mut self, window: &mut Window) -> Option<PathBuf> {
+        window.set_title("Rust Chip8 Emulator ! - ENTER to play - ESC to exit");
+        let mut buffer: Vec<u32> = vec![0; WIDTH * HEIGHT];
+        let mut dirty = true;
+        while window.is_open() {
+            if window.is_key_pressed(Key::Escape, KeyRepeat::No) {
+                return None;
+            }
+            if window.is_key_pressed(Key::Enter, KeyRepeat::No) {
+                if let Some(entry) = self.entries.get(self.selected) {
+                    return Some(entry.path.clone());
+                }
+            }
+            let last = self.entries.len().saturating_sub(1);
+            let selected = if window.is_key_pressed(Key::Up, KeyRepeat::Yes) {
+                self.selected.saturating_sub(1)
+            } else if window.is_key_pressed(Key::Down, KeyRepeat::Yes) {
+                (self.selected + 1).min(last)
+            } else if window.is_key_pressed(Key::PageUp, KeyRepeat::Yes) {
+                self.selected.saturating_sub(VISIBLE_ROWS)
+            } else if window.is_key_pressed(Key::PageDown, KeyRepeat::Yes) {
+                (self.selected + VISIBLE_ROWS).min(last)
+            } else {
+                self.selected
+            };
+            if selected != self.selected {
+                self.selected = selected;
+                dirty = true;
+            }
+
+            if dirty {
+                self.draw(&mut buffer);
+                window.update_with_buffer(&buffer).unwrap();
+                dirty = false;
+            } else {
+                window.update();
+            }
+            thread::sleep(Duration::from_micros(16_667));
+        }
+        None
+    }
+
+    fn draw(&mut self, buffer: &mut [u32]) {
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + VISIBLE_ROWS {
+            self.scroll = self.selected + 1 - VISIBLE_ROWS;
+        }
+
+        buffer.iter_mut().for_each(|pixel| *pixel = 0);
+        let mut canvas = Canvas::new(buffer, WIDTH, HEIGHT);
+        let header = format!("ROMS IN {}", self.directory.display());
+        canvas.draw_text(MARGIN, MARGIN, &header, DIM_COLOR);
+
+        if self.entries.is_empty() {
+            canvas.draw_text(MARGIN, MARGIN + LINE_HEIGHT, "No ROM found.", TEXT_COLOR);
+            return;
+        }
+
+        for (row, entry) in self.entries.iter().enumerate().skip(self.scroll).take(VISIBLE_ROWS) {
+            let y = MARGIN + (row - self.scroll + 1) * LINE_HEIGHT;
+            let color = if row == self.selected {
+                canvas.fill(0, y - 2, WIDTH, LINE_HEIGHT, BOX_COLOR);
+                TEXT_COLOR
+            } else {
+                DIM_COLOR
+            };
+            let line = format!("{:<16} {:>5} B  {}", entry.name, entry.size, entry.platform.name());
+            canvas.draw_text(MARGIN, y, &line, color);
+        }
+
+        // DETAILS OF THE SELECTED ROM AT THE BOTTOM
+        let entry = &self.entries[self.selected];
+        let y = HEIGHT - MARGIN - 3 * LINE_HEIGHT;
+        canvas.fill(0, y - 4, WIDTH, 3 * LINE_HEIGHT + MARGIN + 4, BOX_COLOR);
+        canvas.draw_text(MARGIN, y, entry.title.unwrap_or(&entry.name), TEXT_COLOR);
+        for (line, text) in wrap(entry.description, COLUMNS).iter().take(2).enumerate() {
+            canvas.draw_text(MARGIN, y + (line + 1) * LINE_HEIGHT, text, DIM_COLOR);
+        }
+    }
+}
+
+// SPLITS THE TEXT ON SPACES INTO LINES OF AT MOST columns CHARACTERS
+fn wrap(text: &str, columns: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if line.len() + 1 + word.len() <= columns => {
+                line.push(' ');
+                line.push_str(word);
+            },
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+// None FOR A FILE THAT CANNOT BE READ, OR THAT IS TOO LARGE FOR ANY MACHINE TO LOAD, SO A STRAY
+// DISK IMAGE IN THE DIRECTORY IS NEITHER READ WHOLE NOR LISTED
+fn read_rom(path: &Path) -> Option<Vec<u8>> {
+    let max_size = Addressing::Wrap16.memory_size() - PROGRAM_START as usize;
+    let mut data = Vec::new();
+    File::open(path).ok()?.take(max_size as u64 + 1).read_to_end(&mut data).ok()?;
+    (data.len() <= max_size).then_some(data)
+}
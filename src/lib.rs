@@ -0,0 +1,28 @@
+mod ram;
+mod cpu;
+mod connector;
+mod input;
+mod error;
+mod idle;
+mod instruction;
+mod random;
+mod state;
+mod timing;
+pub mod chip8;
+pub mod config;
+pub mod control_flow;
+pub mod coverage;
+pub mod decompiler;
+pub mod disasm;
+pub mod display;
+pub mod machine;
+pub mod memory_view;
+pub mod profiler;
+pub mod reload;
+pub mod romdb;
+pub mod sprite_sheet;
+
+pub use chip8::{CallFrame, Chip8, Machine};
+pub use config::{Addressing, Config, Quirks, Stack, Timing};
+pub use error::Chip8Error;
+pub use state::StateError;
@@ -0,0 +1,134 @@
+use crate::chip8::Chip8;
+use crate::config::{Addressing, Config, Quirks, Stack, Timing};
+use crate::cpu::START_ADDRESS;
+
+// BUILDS A MACHINE IN A GIVEN STATE, MOSTLY FOR TESTS:
+// Machine::builder().regs([..]).i(0x300).mem(0x300, &[..]).program(&[0x8124]).build()
+#[derive(Default)]
+pub struct MachineBuilder {
+    config: Config,
+    registers: [u8; 16],
+    pc: Option<u16>,
+    i: u16,
+    stack: Vec<u16>,
+    delay_timer: u8,
+    sound_timer: u8,
+    keys: u16,
+    seed: Option<u32>,
+    memory: Vec<(u16, Vec<u8>)>,
+}
+
+impl MachineBuilder {
+    pub fn new() -> MachineBuilder {
+        MachineBuilder::default()
+    }
+
+    pub fn config(mut self, config: Config) -> MachineBuilder {
+        self.config = config;
+        self
+    }
+
+    pub fn quirks(mut self, quirks: Quirks) -> MachineBuilder {
+        self.config.quirks = quirks;
+        self
+    }
+
+    pub fn addressing(mut self, addressing: Addressing) -> MachineBuilder {
+        self.config.addressing = addressing;
+        self
+    }
+
+    pub fn stack_config(mut self, stack: Stack) -> MachineBuilder {
+        self.config.stack = stack;
+        self
+    }
+
+    pub fn timing(mut self, timing: Timing) -> MachineBuilder {
+        self.config.timing = timing;
+        self
+    }
+
+    pub fn regs(mut self, registers: [u8; 16]) -> MachineBuilder {
+        self.registers = registers;
+        self
+    }
+
+    pub fn reg(mut self, x: u8, value: u8) -> MachineBuilder {
+        self.registers[x as usize] = value;
+        self
+    }
+
+    pub fn pc(mut self, pc: u16) -> MachineBuilder {
+        self.pc = Some(pc);
+        self
+    }
+
+    pub fn i(mut self, i: u16) -> MachineBuilder {
+        self.i = i;
+        self
+    }
+
+    // RETURN ADDRESSES, THE LAST ONE BEING THE TOP OF THE STACK
+    pub fn stack(mut self, stack: &[u16]) -> MachineBuilder {
+        self.stack = stack.to_vec();
+        self
+    }
+
+    pub fn delay_timer(mut self, value: u8) -> MachineBuilder {
+        self.delay_timer = value;
+        self
+    }
+
+    pub fn sound_timer(mut self, value: u8) -> MachineBuilder {
+        self.sound_timer = value;
+        self
+    }
+
+    pub fn keys(mut self, mask: u16) -> MachineBuilder {
+        self.keys = mask;
+        self
+    }
+
+    pub fn seed(mut self, seed: u32) -> MachineBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn mem(mut self, address: u16, bytes: &[u8]) -> MachineBuilder {
+        self.memory.push((address, bytes.to_vec()));
+        self
+    }
+
+    // OPCODES WRITTEN FROM THE START ADDRESS, OR FROM pc() WHEN IT WAS GIVEN
+    pub fn program(self, opcodes: &[u16]) -> MachineBuilder {
+        let address = self.pc.unwrap_or(START_ADDRESS);
+        let bytes: Vec<u8> = opcodes.iter().flat_map(|opcode| opcode.to_be_bytes()).collect();
+        self.mem(address, &bytes)
+    }
+
+    pub fn build(self) -> Chip8 {
+        let mut chip8 = Chip8::with_config(self.config);
+        for (x, value) in self.registers.iter().enumerate() {
+            chip8.change_register(x as u8, *value);
+        }
+        if let Some(pc) = self.pc {
+            chip8.change_pc(pc);
+        }
+        chip8.change_i(self.i);
+        chip8.change_stack(&self.stack).expect("the builder stack is deeper than the configured depth");
+        chip8.change_delay_timer(self.delay_timer);
+        chip8.change_sound_timer(self.sound_timer);
+        chip8.change_keys_pressed(self.keys);
+        if let Some(seed) = self.seed {
+            chip8.set_random_seed(seed);
+        }
+        for (address, bytes) in &self.memory {
+            for (offset, byte) in bytes.iter().enumerate() {
+                chip8
+                    .write_memory(address + offset as u16, *byte)
+                    .expect("the builder wrote past the end of memory");
+            }
+        }
+        chip8
+    }
+}
@@ -1,86 +1,473 @@
-use std::fs::File;
-use std::io::Read;
-use chip8::Chip8;
-use connector::Connector;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use chip8_emulator::chip8::{self, Chip8};
+use chip8_emulator::display;
+use chip8_emulator::reload::{self, ReloadOptions};
 use minifb::{KeyRepeat, Key, WindowOptions, Window};
+use hot_reload::Watcher;
+use launcher::Launcher;
+use memory_window::MemoryWindow;
+use osd::Osd;
+use sprite_window::SpriteWindow;
+
+mod hot_reload;
+mod launcher;
+mod memory_window;
+mod osd;
+mod sprite_window;
 
 extern crate beep;
 
-mod ram;
-mod chip8;
-mod cpu;
-mod connector;
-mod input;
-mod display;
+// WHAT FAST-FORWARD CYCLES THROUGH, AS EMULATED FRAMES PER WINDOW FRAME (0 IS UNCAPPED)
+const FAST_FORWARD_SPEEDS: [u32; 4] = [2, 4, 8, 0];
+// SLOW MOTION RUNS ONE EMULATED FRAME EVERY SLOW_MOTION_DIVISOR WINDOW FRAMES
+const SLOW_MOTION_DIVISOR: u32 = 4;
 
-fn main() {
-    // PATH TO THE ROM
-    let mut rom = File::open("data/TICTAC").unwrap();
-    let mut data = Vec::<u8>::new();
+// WINDOW SIZE, 10x THE CHIP-8 SCREEN
+const WIDTH: usize = 640;
+const HEIGHT: usize = 320;
 
-    // LOAD THE ROM
-    rom.read_to_end(&mut data).unwrap();
+// REPORTS WRITTEN WHEN A GAME STOPS, SET FROM THE COMMAND LINE
+#[derive(Default)]
+struct Reports {
+    profile: Option<PathBuf>,
+    coverage: Option<PathBuf>,
+}
 
-    // WINDOW SIZE
-    let width = 640;
-    let height = 320;
+// WHY A GAME STOPPED
+enum Exit {
+    Quit,
+    Launcher,
+}
+
+fn main() {
+    // A ROM GIVEN ON THE COMMAND LINE STARTS RIGHT AWAY, OTHERWISE THE LAUNCHER LISTS THE
+    // ROMS OF THE GIVEN DIRECTORY, OR OF data/
+    let mut argument = None;
+    let mut watch = false;
+    let mut reload_options = ReloadOptions::default();
+    let mut reports = Reports::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--watch" => watch = true,
+            "--keep-registers" => reload_options.keep_registers = true,
+            "--keep-ram" => match args.next().as_deref().and_then(reload::parse_range) {
+                Some(range) => reload_options.keep_ram.push(range),
+                None => {
+                    eprintln!("--keep-ram expects a hexadecimal range such as 300-3FF");
+                    return;
+                }
+            },
+            "--reload-state" => match args.next() {
+                Some(path) => reload_options.state = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("--reload-state expects a save state file");
+                    return;
+                }
+            },
+            "--profile" => match args.next() {
+                Some(path) => reports.profile = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("--profile expects a report file");
+                    return;
+                }
+            },
+            "--coverage" => match args.next() {
+                Some(path) => reports.coverage = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("--coverage expects a report file");
+                    return;
+                }
+            },
+            _ => argument = Some(PathBuf::from(arg)),
+        }
+    }
+    let (directory, mut rom_path) = match argument {
+        Some(path) if path.is_dir() => (path, None),
+        Some(path) => (PathBuf::from("data"), Some(path)),
+        None => (PathBuf::from("data"), None),
+    };
 
-    // WINDOW BUFFER
-    let mut buffer: Vec<u32> = vec![0; width * height];
     let mut window = Window::new(
         "Rust Chip8 Emulator ! - ESC to exit",
-        width,
-        height,
+        WIDTH,
+        HEIGHT,
         WindowOptions::default(),
     )
         .unwrap_or_else(|e| {
             panic!("{}", e);
         });
 
+    // WITHOUT A READABLE DIRECTORY, ONLY THE ROM FROM THE COMMAND LINE CAN BE PLAYED
+    let mut launcher = Launcher::new(&directory)
+        .map_err(|e| eprintln!("{}: {}", directory.display(), e))
+        .ok();
+    while let Some(path) = rom_path.take().or_else(|| launcher.as_mut()?.pick(&mut window)) {
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                continue;
+            }
+        };
+        match run_game(&mut window, &path, data, watch, &reload_options, &reports) {
+            Exit::Quit => break,
+            Exit::Launcher => {},
+        }
+    }
+}
+
+// F8 GOES BACK TO THE LAUNCHER, AS DOES A ROM THAT CRASHES. WITH --watch, THE ROM IS
+// RELOADED WHENEVER ITS FILE CHANGES. WITH --profile AND --coverage, THE PROFILE AND COVERAGE
+// OF THE RUN SINCE THE LAST RESET OR RELOAD ARE WRITTEN WHEN THE GAME STOPS.
+fn run_game(
+    window: &mut Window,
+    path: &Path,
+    mut data: Vec<u8>,
+    watch: bool,
+    reload_options: &ReloadOptions,
+    reports: &Reports,
+) -> Exit {
+    let width = WIDTH;
+    let height = HEIGHT;
+
+    // WINDOW BUFFER
+    let mut buffer: Vec<u32> = vec![0; width * height];
+    window.update_with_buffer(&buffer).unwrap();
+
     let mut chip8 = Chip8::new();
-    chip8.load_rom(&data);
-
-    while window.is_open() && !window.is_key_down(Key::Escape) {
-        let keys_pressed = window.get_keys_pressed(KeyRepeat::Yes);
-        let key: Option<_> = match keys_pressed {
-            Some(keys) => {
-                if keys.len() > 0 {
-                    Some(keys[0])
-                } else {
-                    None
-                }
+    chip8.set_random_seed(random_seed());
+    if let Err(e) = chip8.load_rom(&data) {
+        eprintln!("{}", e);
+        return Exit::Launcher;
+    }
+    reports.start(&mut chip8);
+    let mut beeping = false;
+    let mut watcher = watch.then(|| Watcher::new(path));
+    // SET WHEN THE WHOLE SCREEN HAS TO BE RESCALED, E.G. AFTER SWAPPING THE MACHINE
+    let mut redraw_all = false;
+
+    // F1 PAUSE, F2 RESET, F3 NEXT FRAME WHILE PAUSED, TAB (HELD) FAST-FORWARD,
+    // F4 FAST-FORWARD SPEED, F5 SLOW MOTION, F6 FPS COUNTER, F7 KEYPAD, F8 LAUNCHER,
+    // F9 MEMORY WINDOW, F10 SPRITE WINDOW
+    let mut paused = false;
+    let mut slow_motion = false;
+    let mut fast_forward_index = 0;
+    let mut slow_motion_counter = 0;
+
+    // THE CHIP-8 SCREEN STAYS IN buffer, THE OSD IS DRAWN OVER A COPY OF IT
+    let mut osd = Osd::new();
+    let mut screen: Vec<u32> = vec![0; width * height];
+    let mut osd_was_visible = false;
+    let mut memory_window: Option<MemoryWindow> = None;
+    let mut sprite_window: Option<SpriteWindow> = None;
+
+    // ONE EMULATED FRAME EVERY 1/60 SECOND
+    let frame_duration = Duration::from_micros(16_667);
+    let mut next_frame = Instant::now();
+
+    // FPS / IPS COUNTERS, REFRESHED IN THE TITLE ONCE PER SECOND
+    let mut counter_start = Instant::now();
+    let mut instructions_run = 0;
+    let mut frames_presented = 0;
+    let mut fps = 0;
+    let mut ips = 0;
+    let mut title_state = String::new();
+
+    let mut exit = Exit::Quit;
+    'running: while window.is_open() && !window.is_key_down(Key::Escape) {
+        if window.is_key_pressed(Key::F8, KeyRepeat::No) {
+            exit = Exit::Launcher;
+            break;
+        }
+        if window.is_key_pressed(Key::F1, KeyRepeat::No) {
+            paused = !paused;
+            osd.show_message(if paused { "Paused" } else { "Resumed" });
+        }
+        if window.is_key_pressed(Key::F2, KeyRepeat::No) {
+            // SOFT RESET: A FRESH MACHINE WITH THE SAME CONFIG AND THE ROM RELOADED
+            chip8 = Chip8::with_config(*chip8.get_config());
+            chip8.set_random_seed(random_seed());
+            if let Err(e) = chip8.load_rom(&data) {
+                // PRESENTED RIGHT AWAY, THE LOOP STOPS BEFORE IT WOULD DRAW THE OSD
+                eprintln!("{}", e);
+                osd.show_message(&format!("Reset failed: {}", e));
+                screen.copy_from_slice(&buffer);
+                osd.draw(&mut screen, width, height, chip8.get_keys_pressed());
+                window.update_with_buffer(&screen).unwrap();
+                exit = Exit::Launcher;
+                break;
+            }
+            reports.start(&mut chip8);
+            redraw_all = true;
+            osd.show_message("Reset");
+        }
+        if let Some(new_data) = watcher.as_mut().and_then(Watcher::poll) {
+            match reload::reload(&chip8, &new_data, reload_options, random_seed()) {
+                Ok(reloaded) => {
+                    chip8 = reloaded;
+                    reports.start(&mut chip8);
+                    data = new_data;
+                    redraw_all = true;
+                    osd.show_message("ROM reloaded");
+                },
+                Err(e) => osd.show_message(&format!("Reload failed: {}", e)),
+            }
+        }
+        let advance = window.is_key_pressed(Key::F3, KeyRepeat::Yes);
+        if window.is_key_pressed(Key::F4, KeyRepeat::No) {
+            fast_forward_index = (fast_forward_index + 1) % FAST_FORWARD_SPEEDS.len();
+            match FAST_FORWARD_SPEEDS[fast_forward_index] {
+                0 => osd.show_message("Fast-forward speed uncapped"),
+                speed => osd.show_message(&format!("Fast-forward speed {}x", speed)),
+            }
+        }
+        if window.is_key_pressed(Key::F5, KeyRepeat::No) {
+            slow_motion = !slow_motion;
+            osd.show_message(if slow_motion { "Slow motion on" } else { "Slow motion off" });
+        }
+        if window.is_key_pressed(Key::F6, KeyRepeat::No) {
+            osd.toggle_counters();
+            osd.change_counters(format!("{} FPS  {} IPS", fps, ips));
+        }
+        if window.is_key_pressed(Key::F7, KeyRepeat::No) {
+            osd.toggle_keypad();
+        }
+        if window.is_key_pressed(Key::F9, KeyRepeat::No) {
+            memory_window = match memory_window {
+                Some(_) => None,
+                None => MemoryWindow::new(&chip8),
+            };
+        }
+        if window.is_key_pressed(Key::F10, KeyRepeat::No) {
+            sprite_window = match sprite_window {
+                Some(_) => None,
+                None => SpriteWindow::new(),
+            };
+        }
+        let fast_forward = window.is_key_down(Key::Tab);
+
+        // THE KEYS HELD DOWN RIGHT NOW, A RELEASED KEY IS NO LONGER PRESSED
+        let keys_down = window.get_keys().unwrap_or_default();
+        let mask = keys_down.into_iter().filter_map(get_keycode_by_key).fold(0, |mask, key| mask | 1 << key);
+        chip8.change_keys_pressed(mask);
+
+        let frames_to_run = if paused {
+            advance as u32
+        } else if fast_forward {
+            match FAST_FORWARD_SPEEDS[fast_forward_index] {
+                0 => u32::MAX,
+                speed => speed,
             }
-            None => None
+        } else if slow_motion {
+            slow_motion_counter = (slow_motion_counter + 1) % SLOW_MOTION_DIVISOR;
+            (slow_motion_counter == 0) as u32
+        } else {
+            1
         };
 
-        let chip8_key = Connector::get_keycode_by_key(key);
-        if chip8_key.is_some() {
-            chip8.change_key_pressed(chip8_key);
+        // WHEN FAST-FORWARDING, STOP AT THE NEXT WINDOW FRAME SO THE WINDOW KEEPS UP
+        let deadline = next_frame + frame_duration;
+        let mut frames_run = 0;
+        let instructions_before = chip8.get_instructions_run();
+        while frames_run < frames_to_run && (frames_run == 0 || Instant::now() < deadline) {
+            if let Err(e) = chip8.run_frame() {
+                eprintln!("{}", e);
+                exit = Exit::Launcher;
+                break 'running;
+            }
+            frames_run += 1;
         }
+        instructions_run += chip8.get_instructions_run() - instructions_before;
 
-        chip8.run_instruction();
+        // BEEP WHILE THE SOUND TIMER IS RUNNING, ONLY TALK TO THE SPEAKER ON CHANGES
+        let sound_active = chip8.is_sound_active() && !paused;
+        if sound_active != beeping {
+            beep::beep(if sound_active { 400 } else { 0 }).unwrap();
+            beeping = sound_active;
+        }
 
-        if chip8.get_sound_timer() == 0 {
-            beep::beep(400).unwrap();
-            // IMPLEMENT SOUND
+        // ONLY RESCALE THE ROWS THAT CHANGED SINCE THE LAST FRAME
+        let display_dirty = chip8.is_display_dirty() || redraw_all;
+        if display_dirty {
+            let chip8_buffer = chip8.get_display();
+
+            for y in 0..height {
+                let y_coord = y / 10;
+                if !redraw_all && !chip8.is_display_row_dirty(y_coord) {
+                    continue;
+                }
+                let offset = y * width;
+                for x in 0..width {
+                    let index = display::Display::get_position_from_coords(x / 10, y_coord);
+                    let pixel = chip8_buffer[index];
+                    let color_pixel = match pixel {
+                        0 => 0x0,
+                        1 => 0xffffff,
+                        _ => unreachable!()
+                    };
+                    buffer[offset + x] = color_pixel;
+                }
+            }
+            chip8.clear_display_dirty();
+            redraw_all = false;
         }
 
-        let chip8_buffer = chip8.get_display();
+        // PRESENT WHEN THE SCREEN CHANGED, EVERY FRAME WHILE THE OSD IS UP, AND ONCE MORE WHEN
+        // IT GOES AWAY TO UNCOVER THE SCREEN
+        let osd_visible = osd.is_visible();
+        if osd_visible {
+            screen.copy_from_slice(&buffer);
+            osd.draw(&mut screen, width, height, chip8.get_keys_pressed());
+            window.update_with_buffer(&screen).unwrap();
+            frames_presented += 1;
+        } else if display_dirty || osd_was_visible {
+            window.update_with_buffer(&buffer).unwrap();
+            frames_presented += 1;
+        } else {
+            // NOTHING TO DRAW, ONLY POLL THE WINDOW EVENTS
+            window.update();
+        }
+        osd_was_visible = osd_visible;
 
-        for y in 0..height {
-            let y_coord = y / 10;
-            let offset = y * width;
-            for x in 0..width {
-                let index = display::Display::get_position_from_coords(x / 10, y_coord);
-                let pixel = chip8_buffer[index];
-                let color_pixel = match pixel {
-                    0 => 0x0,
-                    1 => 0xffffff,
-                    _ => unreachable!()
-                };
-                buffer[offset + x] = color_pixel;
+        if memory_window.as_ref().is_some_and(|memory| !memory.is_open()) {
+            memory_window = None;
+        }
+        if let Some(memory) = memory_window.as_mut() {
+            memory.update(&mut chip8, paused);
+        }
+        if sprite_window.as_ref().is_some_and(|sprites| !sprites.is_open()) {
+            sprite_window = None;
+        }
+        if let Some(sprites) = sprite_window.as_mut() {
+            sprites.update(&chip8);
+        }
+
+        let state = if paused {
+            String::from("PAUSED (F3 NEXT FRAME)")
+        } else if fast_forward {
+            match FAST_FORWARD_SPEEDS[fast_forward_index] {
+                0 => String::from("FAST-FORWARD UNCAPPED"),
+                speed => format!("FAST-FORWARD {}x", speed),
             }
+        } else if slow_motion {
+            format!("SLOW MOTION 1/{}", SLOW_MOTION_DIVISOR)
+        } else {
+            String::from("RUNNING")
+        };
+        if counter_start.elapsed() >= Duration::from_secs(1) {
+            fps = frames_presented;
+            ips = instructions_run;
+            counter_start = Instant::now();
+            instructions_run = 0;
+            frames_presented = 0;
+            title_state.clear();
+            osd.change_counters(format!("{} FPS  {} IPS", fps, ips));
+        }
+        if state != title_state {
+            let title = format!(
+                "Rust Chip8 Emulator ! - ESC to exit - {} - {} FPS - {} IPS",
+                state,
+                fps,
+                ips,
+            );
+            window.set_title(&title);
+            title_state = state;
+        }
+
+        // SLEEP UNTIL THE NEXT 60 HZ FRAME INSTEAD OF SPINNING
+        next_frame += frame_duration;
+        let now = Instant::now();
+        if next_frame > now {
+            thread::sleep(next_frame - now);
+        } else {
+            next_frame = now;
+        }
+    }
+
+    if beeping {
+        beep::beep(0).unwrap();
+    }
+    reports.write(&chip8, data.len());
+    exit
+}
+
+impl Reports {
+    fn start(&self, chip8: &mut Chip8) {
+        if self.profile.is_some() {
+            chip8.start_profiler();
+        }
+        if self.coverage.is_some() {
+            chip8.start_coverage();
         }
-        window.update_with_buffer(&buffer).unwrap();
+    }
+
+    // EACH REPORT GOES TO ITS FILE, WITH A SECOND FILE NEXT TO IT: THE FOLDED STACKS FOR
+    // FLAME GRAPHS IN .folded, THE ANNOTATED DISASSEMBLY OF THE ROM IN .lst
+    fn write(&self, chip8: &Chip8, rom_size: usize) {
+        if let (Some(path), Some(profiler)) = (&self.profile, chip8.get_profiler()) {
+            write_report(path, &profiler.report(), "folded", &profiler.folded_stacks());
+        }
+        if let (Some(path), Some(coverage)) = (&self.coverage, chip8.get_coverage()) {
+            let start = chip8::PROGRAM_START as usize;
+            let memory = chip8.get_memory();
+            let report = coverage.report(memory, start, start + rom_size);
+            write_report(path, &report, "lst", &coverage.listing(memory, start, start + rom_size));
+        }
+    }
+}
+
+fn write_report(path: &Path, report: &str, extension: &str, details: &str) {
+    let mut details_path = path.as_os_str().to_owned();
+    details_path.push(".");
+    details_path.push(extension);
+    let written = fs::write(path, report).and_then(|_| fs::write(&details_path, details));
+    if let Err(e) = written {
+        eprintln!("{}: {}", path.display(), e);
+    }
+}
+
+// A DIFFERENT SEED ON EACH LAUNCH SO GAMES DO NOT ALWAYS PLAY THE SAME
+fn random_seed() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos() ^ elapsed.as_secs() as u32)
+        .unwrap_or(0)
+}
+
+fn get_keycode_by_key(key: Key) -> Option<u8> {
+    match key {
+        // ORIGINAL : 1 2 3 C
+        // EMULATOR : A Z E R
+        Key::A => Some(0x1),
+        Key::Z => Some(0x2),
+        Key::E => Some(0x3),
+        Key::R => Some(0xC),
+
+        // ORIGINAL : 4 5 6 D
+        // EMULATOR : Q S D F
+        Key::Q => Some(0x4),
+        Key::S => Some(0x5),
+        Key::D => Some(0x6),
+        Key::F => Some(0xD),
+
+        // ORIGINAL : 7 8 9 E
+        // EMULATOR : W X C V
+        Key::W => Some(0x7),
+        Key::X => Some(0x8),
+        Key::C => Some(0x9),
+        Key::V => Some(0xE),
+
+        // ORIGINAL : A 0 B F
+        // EMULATOR : U I O P
+        Key::U => Some(0xA),
+        Key::I => Some(0x0),
+        Key::O => Some(0xB),
+        Key::P => Some(0xF),
+        _ => None,
     }
 }
@@ -0,0 +1,69 @@
+use crate::chip8::Chip8;
+
+// HEX/ASCII DUMPS OF THE MEMORY FOR DEBUGGERS, WITH THE BYTES CHANGED SINCE THE LAST
+// SNAPSHOT MARKED. TAKE ONE SNAPSHOT PER FRAME TO SEE WHAT EACH FRAME WROTE.
+
+pub const BYTES_PER_ROW: usize = 16;
+
+// WHERE Ram PUTS THE SPRITES OF THE 16 HEX DIGITS
+pub const FONT_START: u16 = 0x000;
+pub const FONT_END: u16 = 0x04F;
+
+pub struct MemoryView {
+    previous: Vec<u8>,
+}
+
+impl MemoryView {
+    pub fn new(chip8: &Chip8) -> MemoryView {
+        MemoryView {
+            previous: chip8.get_memory().to_vec(),
+        }
+    }
+
+    pub fn snapshot(&mut self, chip8: &Chip8) {
+        self.previous.clear();
+        self.previous.extend_from_slice(chip8.get_memory());
+    }
+
+    pub fn has_changed(&self, chip8: &Chip8, address: usize) -> bool {
+        self.previous.get(address) != chip8.get_memory().get(address)
+    }
+
+    // ONE ROW STARTING AT address, WHICH HAS TO BE IN MEMORY, e.g.
+    // 0200  00 E0*A2 2A 60 0C ...  |....`...........|  PC
+    // A '*' AFTER A BYTE MEANS IT CHANGED, PC AND I ARE LISTED WHEN THEY POINT IN THE ROW
+    pub fn dump_row(&self, chip8: &Chip8, address: usize) -> String {
+        let memory = chip8.get_memory();
+        let end = (address + BYTES_PER_ROW).min(memory.len());
+        let mut hex = String::with_capacity(BYTES_PER_ROW * 3);
+        let mut ascii = String::with_capacity(BYTES_PER_ROW);
+        for (offset, byte) in memory[address..end].iter().enumerate() {
+            let marker = if self.has_changed(chip8, address + offset) { '*' } else { ' ' };
+            hex.push_str(&format!("{:02X}{}", byte, marker));
+            ascii.push(if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' });
+        }
+
+        let mut line = format!("{:04X}  {:<48} |{:<16}|", address, hex, ascii);
+        let row = address..end;
+        if row.contains(&(chip8.get_pc() as usize)) {
+            line.push_str("  PC");
+        }
+        if row.contains(&(chip8.get_i() as usize)) {
+            line.push_str("  I");
+        }
+        if let Some(region) = region(address as u16) {
+            line.push_str("  ");
+            line.push_str(region);
+        }
+        line
+    }
+}
+
+// NAME OF THE MEMORY AREA address IS IN, WHEN IT HAS A KNOWN USE
+pub fn region(address: u16) -> Option<&'static str> {
+    if (FONT_START..=FONT_END).contains(&address) {
+        Some("FONT")
+    } else {
+        None
+    }
+}
@@ -0,0 +1,188 @@
+use chip8_emulator::chip8::Chip8;
+use chip8_emulator::memory_view::{self, MemoryView, BYTES_PER_ROW};
+use minifb::{Key, KeyRepeat, Window, WindowOptions};
+use crate::osd::{Canvas, ADVANCE, BOX_COLOR, TEXT_COLOR, TEXT_HEIGHT};
+
+const ROWS: usize = 16;
+const LINE_HEIGHT: usize = TEXT_HEIGHT + 4;
+const MARGIN: usize = 8;
+// "0200  " THEN 3 COLUMNS PER BYTE, THEN THE ASCII AND THE REGION NAME
+const HEX_COLUMN: usize = 6;
+const ASCII_COLUMN: usize = HEX_COLUMN + 3 * BYTES_PER_ROW + 1;
+const REGION_COLUMN: usize = ASCII_COLUMN + BYTES_PER_ROW + 2;
+const WIDTH: usize = 2 * MARGIN + (REGION_COLUMN + 4) * ADVANCE;
+const HEIGHT: usize = 2 * MARGIN + (ROWS + 1) * LINE_HEIGHT;
+
+const DIM_COLOR: u32 = 0x808080;
+const CHANGED_COLOR: u32 = 0xFF4040;
+const FONT_COLOR: u32 = 0x40C040;
+const PC_COLOR: u32 = 0xFFFF00;
+const I_COLOR: u32 = 0x00C0FF;
+// TEXT OVER THE PC AND I HIGHLIGHTS
+const HIGHLIGHTED_TEXT_COLOR: u32 = 0x000000;
+
+// SECOND WINDOW SHOWING 256 BYTES OF MEMORY AROUND A CURSOR. THE ARROWS AND PAGE UP/DOWN
+// MOVE THE CURSOR, P AND I JUMP TO PC AND I, AND WHILE THE GAME IS PAUSED HEX DIGITS
+// OVERWRITE THE BYTE UNDER THE CURSOR, HIGH NIBBLE FIRST.
+pub struct MemoryWindow {
+    window: Window,
+    buffer: Vec<u32>,
+    view: MemoryView,
+    cursor: usize,
+    // HIGH NIBBLE TYPED, WAITING FOR THE LOW ONE
+    pending_nibble: Option<u8>,
+}
+
+impl MemoryWindow {
+    pub fn new(chip8: &Chip8) -> Option<MemoryWindow> {
+        let window = Window::new("Chip8 memory", WIDTH, HEIGHT, WindowOptions::default())
+            .map_err(|e| eprintln!("{}", e))
+            .ok()?;
+        Some(MemoryWindow {
+            window,
+            buffer: vec![0; WIDTH * HEIGHT],
+            view: MemoryView::new(chip8),
+            cursor: chip8.get_pc() as usize % chip8.get_memory().len(),
+            pending_nibble: None,
+        })
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.window.is_open() && !self.window.is_key_down(Key::Escape)
+    }
+
+    // CALLED ONCE PER FRAME, AFTER THE FRAME RAN. CHANGES ARE RELATIVE TO THE PREVIOUS CALL.
+    pub fn update(&mut self, chip8: &mut Chip8, paused: bool) {
+        self.handle_keys(chip8, paused);
+        self.draw(chip8, paused);
+        self.window.update_with_buffer(&self.buffer).unwrap();
+        self.view.snapshot(chip8);
+    }
+
+    fn handle_keys(&mut self, chip8: &mut Chip8, paused: bool) {
+        let size = chip8.get_memory().len();
+        let moves = [
+            (Key::Left, size - 1),
+            (Key::Right, 1),
+            (Key::Up, size - BYTES_PER_ROW),
+            (Key::Down, BYTES_PER_ROW),
+            (Key::PageUp, size - ROWS * BYTES_PER_ROW),
+            (Key::PageDown, ROWS * BYTES_PER_ROW),
+        ];
+        for (key, offset) in moves {
+            if self.window.is_key_pressed(key, KeyRepeat::Yes) {
+                self.cursor = (self.cursor + offset) % size;
+                self.pending_nibble = None;
+            }
+        }
+        if self.window.is_key_pressed(Key::P, KeyRepeat::No) {
+            self.cursor = chip8.get_pc() as usize % size;
+            self.pending_nibble = None;
+        }
+        if self.window.is_key_pressed(Key::I, KeyRepeat::No) {
+            self.cursor = chip8.get_i() as usize % size;
+            self.pending_nibble = None;
+        }
+
+        if !paused {
+            self.pending_nibble = None;
+            return;
+        }
+        let typed = self.window.get_keys_pressed(KeyRepeat::No).unwrap_or_default();
+        for nibble in typed.into_iter().filter_map(hex_digit) {
+            match self.pending_nibble.take() {
+                None => self.pending_nibble = Some(nibble),
+                Some(high) => {
+                    // THE CURSOR IS ALWAYS IN MEMORY, THE WRITE CANNOT FAIL
+                    let _ = chip8.write_memory(self.cursor as u16, (high << 4) | nibble);
+                    self.cursor = (self.cursor + 1) % size;
+                },
+            }
+        }
+    }
+
+    fn draw(&mut self, chip8: &Chip8, paused: bool) {
+        let memory = chip8.get_memory();
+        let page = ROWS * BYTES_PER_ROW;
+        // THE CURSOR ROW STAYS IN THE MIDDLE OF THE WINDOW WHEN IT CAN
+        let cursor_row = self.cursor / BYTES_PER_ROW;
+        let first_row = cursor_row.saturating_sub(ROWS / 2).min((memory.len() - page) / BYTES_PER_ROW);
+        let pc = chip8.get_pc() as usize;
+        let i = chip8.get_i() as usize;
+
+        self.buffer.iter_mut().for_each(|pixel| *pixel = 0);
+        let mut canvas = Canvas::new(&mut self.buffer, WIDTH, HEIGHT);
+        let header = if paused {
+            format!("PC {:04X}  I {:04X}  CURSOR {:04X}  TYPE HEX TO EDIT", pc, i, self.cursor)
+        } else {
+            format!("PC {:04X}  I {:04X}  CURSOR {:04X}  PAUSE TO EDIT", pc, i, self.cursor)
+        };
+        canvas.draw_text(MARGIN, MARGIN, &header, DIM_COLOR);
+
+        for row in 0..ROWS {
+            let address = (first_row + row) * BYTES_PER_ROW;
+            let y = MARGIN + (row + 1) * LINE_HEIGHT;
+            canvas.draw_text(MARGIN, y, &format!("{:04X}", address), DIM_COLOR);
+            for offset in 0..BYTES_PER_ROW {
+                let byte_address = address + offset;
+                let byte = memory[byte_address];
+                let x = MARGIN + (HEX_COLUMN + 3 * offset) * ADVANCE;
+                // PC COVERS THE TWO BYTES OF THE NEXT OPCODE
+                let background = if byte_address == pc || byte_address == pc + 1 {
+                    Some(PC_COLOR)
+                } else if byte_address == i {
+                    Some(I_COLOR)
+                } else if byte_address == self.cursor {
+                    Some(BOX_COLOR)
+                } else {
+                    None
+                };
+                let color = if self.view.has_changed(chip8, byte_address) {
+                    CHANGED_COLOR
+                } else if background.is_some_and(|color| color != BOX_COLOR) {
+                    HIGHLIGHTED_TEXT_COLOR
+                } else if memory_view::region(byte_address as u16).is_some() {
+                    FONT_COLOR
+                } else {
+                    TEXT_COLOR
+                };
+                if let Some(background) = background {
+                    canvas.fill(x - 2, y - 2, 2 * ADVANCE + 2, LINE_HEIGHT, background);
+                }
+                canvas.draw_text(x, y, &format!("{:02X}", byte), color);
+                if byte_address == self.cursor {
+                    // UNDERLINE THE CURSOR, WHATEVER ELSE HIGHLIGHTS THE BYTE
+                    canvas.fill(x - 2, y + TEXT_HEIGHT + 1, 2 * ADVANCE + 2, 2, TEXT_COLOR);
+                }
+                let ascii = if byte.is_ascii_graphic() { byte as char } else { '.' };
+                let ascii_x = MARGIN + (ASCII_COLUMN + offset) * ADVANCE;
+                canvas.draw_text(ascii_x, y, &ascii.to_string(), DIM_COLOR);
+            }
+            if let Some(region) = memory_view::region(address as u16) {
+                canvas.draw_text(MARGIN + REGION_COLUMN * ADVANCE, y, region, FONT_COLOR);
+            }
+        }
+    }
+}
+
+fn hex_digit(key: Key) -> Option<u8> {
+    match key {
+        Key::Key0 | Key::NumPad0 => Some(0x0),
+        Key::Key1 | Key::NumPad1 => Some(0x1),
+        Key::Key2 | Key::NumPad2 => Some(0x2),
+        Key::Key3 | Key::NumPad3 => Some(0x3),
+        Key::Key4 | Key::NumPad4 => Some(0x4),
+        Key::Key5 | Key::NumPad5 => Some(0x5),
+        Key::Key6 | Key::NumPad6 => Some(0x6),
+        Key::Key7 | Key::NumPad7 => Some(0x7),
+        Key::Key8 | Key::NumPad8 => Some(0x8),
+        Key::Key9 | Key::NumPad9 => Some(0x9),
+        Key::A => Some(0xA),
+        Key::B => Some(0xB),
+        Key::C => Some(0xC),
+        Key::D => Some(0xD),
+        Key::E => Some(0xE),
+        Key::F => Some(0xF),
+        _ => None,
+    }
+}
@@ -0,0 +1,208 @@
+use std::time::{Duration, Instant};
+
+// ON-SCREEN DISPLAY OF THE MINIFB FRONTEND, DRAWN OVER THE SCALED FRAMEBUFFER.
+// IT HAS ITS OWN 5x7 FONT: THE CHIP-8 FONT IN RAM ONLY HAS THE 16 HEX DIGITS.
+
+// HOW LONG A MESSAGE STAYS ON SCREEN
+const MESSAGE_DURATION: Duration = Duration::from_secs(2);
+
+// EACH FONT PIXEL IS DRAWN AS A SCALE x SCALE SQUARE
+const SCALE: usize = 2;
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+pub const TEXT_HEIGHT: usize = GLYPH_HEIGHT * SCALE;
+// ONE EMPTY COLUMN BETWEEN CHARACTERS
+pub const ADVANCE: usize = (GLYPH_WIDTH + 1) * SCALE;
+// SPACE LEFT AROUND THE TEXT INSIDE ITS BACKGROUND BOX
+const PADDING: usize = 4;
+
+pub const TEXT_COLOR: u32 = 0xFFFF00;
+pub const BOX_COLOR: u32 = 0x202020;
+const KEY_PRESSED_COLOR: u32 = 0xFFFF00;
+
+// KEYS AS THEY ARE LAID OUT ON THE COSMAC VIP KEYPAD
+const KEYPAD: [[u8; 4]; 4] = [
+    [0x1, 0x2, 0x3, 0xC],
+    [0x4, 0x5, 0x6, 0xD],
+    [0x7, 0x8, 0x9, 0xE],
+    [0xA, 0x0, 0xB, 0xF],
+];
+const KEY_SIZE: usize = TEXT_HEIGHT + 2 * PADDING;
+
+pub struct Osd {
+    message: Option<(String, Instant)>,
+    counters: Option<String>,
+    show_keypad: bool,
+}
+
+impl Osd {
+    pub fn new() -> Osd {
+        Osd {
+            message: None,
+            counters: None,
+            show_keypad: false,
+        }
+    }
+
+    // REPLACES THE CURRENT MESSAGE, IF ANY
+    pub fn show_message(&mut self, text: &str) {
+        self.message = Some((text.to_string(), Instant::now()));
+    }
+
+    pub fn toggle_counters(&mut self) {
+        self.counters = match self.counters {
+            Some(_) => None,
+            None => Some(String::new()),
+        };
+    }
+
+    // ONLY KEPT WHILE THE COUNTERS ARE SHOWN
+    pub fn change_counters(&mut self, text: String) {
+        if self.counters.is_some() {
+            self.counters = Some(text);
+        }
+    }
+
+    pub fn toggle_keypad(&mut self) {
+        self.show_keypad = !self.show_keypad;
+    }
+
+    // DROPS THE MESSAGE ONCE IT EXPIRED, THEN TELLS IF ANYTHING IS LEFT TO DRAW
+    pub fn is_visible(&mut self) -> bool {
+        if let Some((_, shown_at)) = &self.message {
+            if shown_at.elapsed() >= MESSAGE_DURATION {
+                self.message = None;
+            }
+        }
+        self.message.is_some() || self.counters.is_some() || self.show_keypad
+    }
+
+    // keys IS THE MASK OF THE PRESSED CHIP-8 KEYS, BIT N FOR KEY N
+    pub fn draw(&self, buffer: &mut [u32], width: usize, height: usize, keys: u16) {
+        let mut canvas = Canvas::new(buffer, width, height);
+        if let Some(counters) = &self.counters {
+            canvas.draw_text_box(PADDING, PADDING, counters);
+        }
+        if let Some((message, _)) = &self.message {
+            let y = height.saturating_sub(TEXT_HEIGHT + 3 * PADDING);
+            canvas.draw_text_box(PADDING, y, message);
+        }
+        if self.show_keypad {
+            let left = width.saturating_sub(4 * KEY_SIZE + PADDING);
+            for (row, keys_row) in KEYPAD.iter().enumerate() {
+                for (column, key) in keys_row.iter().enumerate() {
+                    let pressed = keys & (1 << key) != 0;
+                    let (background, foreground) = if pressed {
+                        (KEY_PRESSED_COLOR, BOX_COLOR)
+                    } else {
+                        (BOX_COLOR, TEXT_COLOR)
+                    };
+                    let x = left + column * KEY_SIZE;
+                    let y = PADDING + row * KEY_SIZE;
+                    // ONE PIXEL OF GAP SO THE KEYS STAY APART
+                    canvas.fill(x, y, KEY_SIZE - 1, KEY_SIZE - 1, background);
+                    let label = format!("{:X}", key);
+                    canvas.draw_text(x + (KEY_SIZE - GLYPH_WIDTH * SCALE) / 2, y + PADDING, &label, foreground);
+                }
+            }
+        }
+    }
+}
+
+// TEXT AND BOXES OVER A WINDOW BUFFER, ALSO USED BY THE LAUNCHER
+pub struct Canvas<'a> {
+    buffer: &'a mut [u32],
+    width: usize,
+    height: usize,
+}
+
+impl Canvas<'_> {
+    pub fn new(buffer: &mut [u32], width: usize, height: usize) -> Canvas<'_> {
+        Canvas { buffer, width, height }
+    }
+
+    // PIXELS OUTSIDE THE WINDOW ARE DROPPED
+    pub fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, color: u32) {
+        for py in y..(y + height).min(self.height) {
+            for px in x..(x + width).min(self.width) {
+                self.buffer[py * self.width + px] = color;
+            }
+        }
+    }
+
+    fn draw_text_box(&mut self, x: usize, y: usize, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let width = text.chars().count() * ADVANCE - SCALE + 2 * PADDING;
+        let height = TEXT_HEIGHT + 2 * PADDING;
+        self.fill(x, y, width, height, BOX_COLOR);
+        self.draw_text(x + PADDING, y + PADDING, text, TEXT_COLOR);
+    }
+
+    pub fn draw_text(&mut self, x: usize, y: usize, text: &str, color: u32) {
+        for (index, c) in text.chars().enumerate() {
+            let left = x + index * ADVANCE;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for column in 0..GLYPH_WIDTH {
+                    if bits & (0x10 >> column) != 0 {
+                        self.fill(left + column * SCALE, y + row * SCALE, SCALE, SCALE, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ROWS OF THE CHARACTER FROM TOP TO BOTTOM, BIT 4 IS THE LEFTMOST PIXEL.
+// LOWERCASE LETTERS ARE DRAWN AS UPPERCASE.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        ' ' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// COUNTS WHERE A ROM SPENDS ITS INSTRUCTIONS. TIME IS MEASURED IN INSTRUCTIONS RUN, NOT IN
+// HOST TIME, SO TWO RUNS OF THE SAME INPUTS GIVE THE SAME PROFILE.
+#[derive(Default)]
+pub struct Profiler {
+    total: u64,
+    pc_hits: HashMap<u16, u64>,
+    class_hits: HashMap<&'static str, u64>,
+    // ENTRY ADDRESSES OF THE SUBROUTINES BEING RUN, MIRRORING THE CPU STACK
+    stack: Vec<u16>,
+    calls: HashMap<u16, u64>,
+    // INSTRUCTIONS RUN WITH EACH CALL STACK, THE FIRST ONE BEING THE OUTERMOST
+    folded: HashMap<Vec<u16>, u64>,
+    // ITERATIONS OF EACH DELAY TIMER WAIT LOOP, BY ADDRESS OF ITS FX07
+    busy_waits: HashMap<u16, u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubroutineStats {
+    pub address: u16,
+    pub calls: u64,
+    // INSTRUCTIONS RUN IN THE SUBROUTINE ITSELF
+    pub self_instructions: u64,
+    // INCLUDING THE SUBROUTINES IT CALLED
+    pub total_instructions: u64,
+}
+
+impl Profiler {
+    pub fn new() -> Profiler {
+        Profiler::default()
+    }
+
+    // CALLED BEFORE EACH INSTRUCTION, WITH ITS PC AND OPCODE AND THE CPU STACK AND MEMORY
+    pub fn record(&mut self, pc: u16, opcode: u16, cpu_stack: &[u16], memory: &[u8]) {
+        // FRAMES FOUND ON THE FIRST CALL WERE PUSHED BEFORE PROFILING STARTED
+        self.sync_stack(cpu_stack, memory, self.total > 0);
+        self.total += 1;
+        *self.pc_hits.entry(pc).or_insert(0) += 1;
+        *self.class_hits.entry(opcode_class(opcode)).or_insert(0) += 1;
+        // A CALL COUNTS FOR THE CALLER AND A RETURN FOR THE CALLEE
+        match self.folded.get_mut(self.stack.as_slice()) {
+            Some(count) => *count += 1,
+            None => {
+                self.folded.insert(self.stack.clone(), 1);
+            },
+        }
+
+        // FX07 / 3X00 / 1NNN BACK TO THE FX07: WAITING FOR THE DELAY TIMER TO REACH 0
+        if opcode & 0xF000 == 0x1000 {
+            let target = opcode & 0x0FFF;
+            if target as usize + 4 == pc as usize {
+                let read = |address: usize| -> Option<u16> {
+                    Some(u16::from_be_bytes([*memory.get(address)?, *memory.get(address + 1)?]))
+                };
+                if let (Some(load), Some(skip)) = (read(target as usize), read(target as usize + 2)) {
+                    let x = load & 0x0F00;
+                    if load & 0xF0FF == 0xF007 && skip == 0x3000 | x {
+                        *self.busy_waits.entry(target).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    // THE ENTRY OF A NEW FRAME IS THE NNN OF THE 2NNN RIGHT BEFORE ITS RETURN ADDRESS
+    fn sync_stack(&mut self, cpu_stack: &[u16], memory: &[u8], count_calls: bool) {
+        self.stack.truncate(cpu_stack.len());
+        while self.stack.len() < cpu_stack.len() {
+            let caller = cpu_stack[self.stack.len()].wrapping_sub(2) as usize;
+            let entry = match (memory.get(caller), memory.get(caller + 1)) {
+                (Some(high), Some(low)) => u16::from_be_bytes([*high, *low]) & 0x0FFF,
+                _ => 0,
+            };
+            if count_calls {
+                *self.calls.entry(entry).or_insert(0) += 1;
+            }
+            self.stack.push(entry);
+        }
+    }
+
+    pub fn get_total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn get_hits(&self, pc: u16) -> u64 {
+        self.pc_hits.get(&pc).copied().unwrap_or(0)
+    }
+
+    // THE count MOST EXECUTED ADDRESSES, MOST EXECUTED FIRST
+    pub fn hottest(&self, count: usize) -> Vec<(u16, u64)> {
+        let mut hits: Vec<(u16, u64)> = self.pc_hits.iter().map(|(pc, hits)| (*pc, *hits)).collect();
+        hits.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        hits.truncate(count);
+        hits
+    }
+
+    // INSTRUCTIONS RUN PER OPCODE CLASS (e.g. "8XY4"), MOST EXECUTED FIRST
+    pub fn classes(&self) -> Vec<(&'static str, u64)> {
+        let mut classes: Vec<(&'static str, u64)> = self.class_hits.iter().map(|(class, hits)| (*class, *hits)).collect();
+        classes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        classes
+    }
+
+    // SUBROUTINES THAT RAN, MOST EXPENSIVE FIRST
+    pub fn subroutines(&self) -> Vec<SubroutineStats> {
+        let mut stats: HashMap<u16, SubroutineStats> = HashMap::new();
+        for (stack, count) in &self.folded {
+            for (depth, address) in stack.iter().enumerate() {
+                let entry = stats.entry(*address).or_insert(SubroutineStats {
+                    address: *address,
+                    calls: self.calls.get(address).copied().unwrap_or(0),
+                    self_instructions: 0,
+                    total_instructions: 0,
+                });
+                // A RECURSIVE SUBROUTINE ONLY COUNTS ONCE PER STACK
+                if !stack[..depth].contains(address) {
+                    entry.total_instructions += count;
+                }
+                if depth + 1 == stack.len() {
+                    entry.self_instructions += count;
+                }
+            }
+        }
+        let mut stats: Vec<SubroutineStats> = stats.into_values().collect();
+        stats.sort_by(|a, b| b.total_instructions.cmp(&a.total_instructions).then(a.address.cmp(&b.address)));
+        stats
+    }
+
+    // DELAY TIMER WAIT LOOPS, BY ADDRESS OF THEIR FX07, AND HOW MANY TIMES THEY LOOPED
+    pub fn busy_waits(&self) -> Vec<(u16, u64)> {
+        let mut waits: Vec<(u16, u64)> = self.busy_waits.iter().map(|(pc, count)| (*pc, *count)).collect();
+        waits.sort();
+        waits
+    }
+
+    // ONE LINE PER CALL STACK, "main;sub_0300;sub_0412 1234", AS READ BY flamegraph.pl
+    // AND OTHER FLAME GRAPH TOOLS
+    pub fn folded_stacks(&self) -> String {
+        let mut lines: Vec<String> = self
+            .folded
+            .iter()
+            .map(|(stack, count)| {
+                let mut line = String::from("main");
+                for address in stack {
+                    let _ = write!(line, ";sub_{:04X}", address);
+                }
+                format!("{} {}", line, count)
+            })
+            .collect();
+        lines.sort();
+        lines.iter().map(|line| format!("{}\n", line)).collect()
+    }
+
+    pub fn report(&self) -> String {
+        let percent = |count: u64| count as f64 * 100.0 / self.total.max(1) as f64;
+        let mut report = String::new();
+        let _ = writeln!(report, "{} instructions", self.total);
+
+        let _ = writeln!(report, "\nHottest addresses");
+        for (pc, hits) in self.hottest(20) {
+            let _ = writeln!(report, "  {:04X}  {:>10}  {:5.1}%", pc, hits, percent(hits));
+        }
+
+        let _ = writeln!(report, "\nOpcodes");
+        for (class, hits) in self.classes() {
+            let _ = writeln!(report, "  {}  {:>10}  {:5.1}%", class, hits, percent(hits));
+        }
+
+        let _ = writeln!(report, "\nSubroutines      calls        self       total");
+        for stats in self.subroutines() {
+            let _ = writeln!(
+                report,
+                "  {:04X}  {:>10}  {:>10}  {:>10}  {:5.1}%",
+                stats.address,
+                stats.calls,
+                stats.self_instructions,
+                stats.total_instructions,
+                percent(stats.total_instructions),
+            );
+        }
+
+        let _ = writeln!(report, "\nDelay timer busy waits");
+        for (pc, iterations) in self.busy_waits() {
+            // EACH ITERATION IS 3 INSTRUCTIONS
+            let _ = writeln!(report, "  {:04X}  {:>10} iterations  {:5.1}%", pc, iterations, percent(3 * iterations));
+        }
+        report
+    }
+}
+
+// NAME OF THE INSTRUCTION, WITH ITS OPERANDS AS LETTERS
+pub fn opcode_class(opcode: u16) -> &'static str {
+    const ALU: [&str; 16] = [
+        "8XY0", "8XY1", "8XY2", "8XY3", "8XY4", "8XY5", "8XY6", "8XY7",
+        "????", "????", "????", "????", "????", "????", "8XYE", "????",
+    ];
+    match (opcode >> 12, opcode & 0xFF) {
+        (0x0, 0xE0) => "00E0",
+        (0x0, 0xEE) => "00EE",
+        (0x0, _) => "0NNN",
+        (0x1, _) => "1NNN",
+        (0x2, _) => "2NNN",
+        (0x3, _) => "3XNN",
+        (0x4, _) => "4XNN",
+        (0x5, _) => "5XY0",
+        (0x6, _) => "6XNN",
+        (0x7, _) => "7XNN",
+        (0x8, _) => ALU[(opcode & 0xF) as usize],
+        (0x9, _) => "9XY0",
+        (0xA, _) => "ANNN",
+        (0xB, _) => "BNNN",
+        (0xC, _) => "CXNN",
+        (0xD, _) => "DXYN",
+        (0xE, 0x9E) => "EX9E",
+        (0xE, 0xA1) => "EXA1",
+        (0xF, 0x07) => "FX07",
+        (0xF, 0x0A) => "FX0A",
+        (0xF, 0x15) => "FX15",
+        (0xF, 0x18) => "FX18",
+        (0xF, 0x1E) => "FX1E",
+        (0xF, 0x29) => "FX29",
+        (0xF, 0x33) => "FX33",
+        (0xF, 0x55) => "FX55",
+        (0xF, 0x65) => "FX65",
+        _ => "????",
+    }
+}
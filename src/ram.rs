@@ -1,11 +1,24 @@
+use crate::error::Chip8Error;
+use crate::instruction::Instruction;
+use crate::state::{StateError, StateReader, StateWriter};
+
+pub const MEMORY_SIZE: usize = 4096;
+
 pub struct Ram {
-    pub(crate) mem: [u8; 4096],
+    mem: Vec<u8>,
+    // THE INSTRUCTION AT EACH ADDRESS, ONCE THE CPU HAS DECODED IT. WRITING A BYTE DROPS THE
+    // TWO INSTRUCTIONS IT CAN BE PART OF.
+    decoded: Vec<Option<Instruction>>,
+    // WITHOUT IT NOTHING IS KEPT AND EACH INSTRUCTION IS DECODED EVERY TIME IT RUNS
+    decode_cache: bool,
 }
 
 impl Ram {
-    pub fn new() -> Ram {
+    pub fn new(size: usize) -> Ram {
         let mut ram = Ram {
-            mem: [0; 4096],
+            mem: vec![0; size],
+            decoded: vec![None; size],
+            decode_cache: true,
         };
 
         let sprites: [[u8; 5]; 16] = [
@@ -39,11 +52,62 @@ impl Ram {
         ram
     }
 
-    pub fn write_byte(&mut self, address: u16, value: u8) {
-        self.mem[address as usize] = value;
+    pub fn get_size(&self) -> usize {
+        self.mem.len()
+    }
+
+    pub fn get_bytes(&self) -> &[u8] {
+        &self.mem
+    }
+
+    // KEEPS THE CONTENT THAT STILL FITS, NEW MEMORY IS ZEROED
+    pub fn change_size(&mut self, size: usize) {
+        self.mem.resize(size, 0);
+        // AN INSTRUCTION AT THE OLD LAST ADDRESS WRAPPED AROUND TO ADDRESS 0
+        self.decoded = vec![None; size];
+    }
+
+    pub fn write_byte(&mut self, address: u16, value: u8) -> Result<(), Chip8Error> {
+        let byte = self.mem.get_mut(address as usize).ok_or(Chip8Error::AddressOutOfRange(address as usize))?;
+        *byte = value;
+        let size = self.mem.len();
+        self.decoded[address as usize] = None;
+        self.decoded[(address as usize + size - 1) % size] = None;
+        Ok(())
+    }
+
+    pub fn read_byte(&self, address: u16) -> Result<u8, Chip8Error> {
+        self.mem.get(address as usize).copied().ok_or(Chip8Error::AddressOutOfRange(address as usize))
+    }
+
+    pub fn change_decode_cache(&mut self, enabled: bool) {
+        self.decode_cache = enabled;
+        if !enabled {
+            self.decoded.fill(None);
+        }
+    }
+
+    pub fn get_decoded(&self, address: u16) -> Option<Instruction> {
+        self.decoded.get(address as usize).copied().flatten()
+    }
+
+    pub fn cache_decoded(&mut self, address: u16, instruction: Instruction) {
+        if !self.decode_cache {
+            return;
+        }
+        if let Some(decoded) = self.decoded.get_mut(address as usize) {
+            *decoded = Some(instruction);
+        }
+    }
+
+    pub fn save_state(&self, writer: &mut StateWriter) {
+        writer.write_bytes(&self.mem);
     }
 
-    pub fn read_byte(&self, address: u16) -> u8 {
-        self.mem[address as usize]
+    pub fn load_state(&mut self, reader: &mut StateReader) -> Result<(), StateError> {
+        let mem = reader.read_bytes(self.mem.len())?;
+        self.mem.copy_from_slice(mem);
+        self.decoded.fill(None);
+        Ok(())
     }
 }
@@ -0,0 +1,33 @@
+// SMALL XORSHIFT GENERATOR SO THE CORE DOES NOT NEED AN OS RANDOM SOURCE.
+// THE SAME SEED ALWAYS GIVES THE SAME SEQUENCE, WHICH KEEPS RUNS REPRODUCIBLE.
+pub const DEFAULT_SEED: u32 = 0x2545_F491;
+
+pub struct Random {
+    state: u32,
+}
+
+impl Random {
+    pub fn new(seed: u32) -> Random {
+        let mut random = Random { state: 0 };
+        random.set_seed(seed);
+        random
+    }
+
+    pub fn set_seed(&mut self, seed: u32) {
+        // XORSHIFT NEVER LEAVES 0, SO A 0 SEED FALLS BACK TO THE DEFAULT ONE
+        self.state = if seed == 0 { DEFAULT_SEED } else { seed };
+    }
+
+    pub fn get_state(&self) -> u32 {
+        self.state
+    }
+
+    pub fn next_byte(&mut self) -> u8 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        (x >> 24) as u8
+    }
+}
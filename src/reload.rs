@@ -0,0 +1,49 @@
+use std::fs;
+use std::path::PathBuf;
+use crate::chip8::Chip8;
+
+// RELOADING A ROM WHILE IT RUNS, E.G. WHEN ITS FILE IS REBUILT: A FRESH MACHINE RUNS THE NEW
+// ROM, AND THE OPTIONS PICK WHAT IT TAKES OVER FROM THE OLD ONE SO THE GAME CAN GO ON WHERE IT
+// WAS.
+
+#[derive(Debug, Clone, Default)]
+pub struct ReloadOptions {
+    // V0 TO VF AND I
+    pub keep_registers: bool,
+    // INCLUSIVE ADDRESS RANGES COPIED FROM THE OLD MACHINE
+    pub keep_ram: Vec<(u16, u16)>,
+    // SAVE STATE LOADED BEFORE THE NEW ROM IS WRITTEN OVER ITS PROGRAM AREA
+    pub state: Option<PathBuf>,
+}
+
+// THE ERROR IS A MESSAGE FOR THE PLAYER, THE MACHINE RUNNING THE OLD ROM IS LEFT AS IT WAS
+pub fn reload(old: &Chip8, data: &[u8], options: &ReloadOptions, seed: u32) -> Result<Chip8, String> {
+    let mut chip8 = Chip8::with_config(*old.get_config());
+    chip8.set_random_seed(seed);
+    if let Some(path) = &options.state {
+        let state = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        chip8.load_state(&state).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    chip8.load_rom(data).map_err(|e| e.to_string())?;
+    if options.keep_registers {
+        for (x, value) in old.get_registers().iter().enumerate() {
+            chip8.change_register(x as u8, *value);
+        }
+        chip8.change_i(old.get_i());
+    }
+    for &(start, end) in &options.keep_ram {
+        for address in start..=end {
+            let value = old.read_memory(address).map_err(|e| e.to_string())?;
+            chip8.write_memory(address, value).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(chip8)
+}
+
+// "300-3FF" OR "300", IN HEXADECIMAL
+pub fn parse_range(text: &str) -> Option<(u16, u16)> {
+    let (start, end) = text.split_once('-').unwrap_or((text, text));
+    let start = u16::from_str_radix(start, 16).ok()?;
+    let end = u16::from_str_radix(end, 16).ok()?;
+    if start <= end { Some((start, end)) } else { None }
+}
@@ -0,0 +1,100 @@
+use crate::chip8::MAX_ROM_SIZE;
+
+// WHAT A ROM WAS WRITTEN FOR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Chip8,
+    Schip,
+    XoChip,
+}
+
+impl Platform {
+    pub fn name(self) -> &'static str {
+        match self {
+            Platform::Chip8 => "CHIP-8",
+            Platform::Schip => "SCHIP",
+            Platform::XoChip => "XO-CHIP",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RomInfo {
+    pub title: &'static str,
+    pub platform: Platform,
+    pub description: &'static str,
+}
+
+// KNOWN ROMS, BY FNV-1A HASH OF THEIR CONTENT
+const ROMS: [(u64, RomInfo); 23] = [
+    (0xe59fd57fa44ecb40, rom("15 Puzzle", "Slide the tiles back into order from 1 to 15.")),
+    (0x0fd332d0bc68c9f2, rom("Blinky", "Pac-Man clone: eat the dots and avoid the ghosts.")),
+    (0x29bcab9b664d212b, rom("Blitz", "Bomb the buildings flat before the plane lands on them.")),
+    (0xc86e8ff63fce668c, rom("Brix", "Breakout clone: bounce the ball to clear the bricks.")),
+    (0xadf99268db3c3bc9, rom("Connect 4", "Drop discs to line up four of them, for two players.")),
+    (0x1bbb10c8e5cadbb5, rom("Guess", "Think of a number, the program finds it.")),
+    (0x3f58eb4fa83dcd98, rom("Hidden", "Memory game: find the pairs of matching cards.")),
+    (0x8e547ebb12c026b4, rom("Space Invaders", "Shoot the invaders before they reach the ground.")),
+    (0xa8e9391ebb18df6f, rom("Kaleidoscope", "Draw with the keypad and watch the pattern mirror.")),
+    (0x25e96e1086ce43cb, rom("Maze", "Draws a random maze.")),
+    (0x43def5533f6d8d25, rom("Merlin", "Simon-like game: repeat the sequence of squares.")),
+    (0x71cdb8b926f1b988, rom("Missile Command", "Shoot down the missiles to protect the cities.")),
+    (0x624b3eed64313f42, rom("Pong", "Two player Pong.")),
+    (0x0f81c6a74dcd366e, rom("Pong 2", "Two player Pong with a few tweaks.")),
+    (0x36f264b8f72349a6, rom("Puzzle", "Slide the tiles back into order.")),
+    (0xec7ca0de3e110327, rom("Syzygy", "Snake-like game: grow without hitting anything.")),
+    (0x3e2c2d43b296b74c, rom("Tank", "Drive the tank and shoot the moving target.")),
+    (0x04eb2109dc29b1ab, rom("Tetris", "Rotate and drop the pieces to complete lines.")),
+    (0x56049e83866b207d, rom("Tic-Tac-Toe", "Tic-tac-toe for two players.")),
+    (0x8d8a02fa3a2ed293, rom("UFO", "Shoot the UFOs flying across the sky.")),
+    (0xcdaa32787deaa913, rom("Vertical Brix", "Breakout turned on its side.")),
+    (0xeae1357f230d90c5, rom("Vers", "Light cycles for two players: do not hit a wall.")),
+    (0xb7e1d74b387bede6, rom("Wipe Off", "Breakout-like game: wipe off all the dots.")),
+];
+
+// ALL THE BUNDLED ROMS ARE CHIP-8 ONES
+const fn rom(title: &'static str, description: &'static str) -> RomInfo {
+    RomInfo { title, platform: Platform::Chip8, description }
+}
+
+pub fn lookup(data: &[u8]) -> Option<RomInfo> {
+    let hash = fnv1a(data);
+    ROMS.iter().find(|(known, _)| *known == hash).map(|(_, info)| *info)
+}
+
+// GUESSES THE PLATFORM FROM THE OPCODES ONLY THE EXTENSIONS HAVE. DATA MIXED WITH THE CODE
+// CAN LOOK LIKE ONE OF THEM, SO lookup() SHOULD BE PREFERRED WHEN IT KNOWS THE ROM.
+pub fn detect_platform(data: &[u8]) -> Platform {
+    if data.len() > MAX_ROM_SIZE {
+        return Platform::XoChip;
+    }
+    let mut platform = Platform::Chip8;
+    for pair in data.chunks_exact(2) {
+        let opcode = u16::from_be_bytes([pair[0], pair[1]]);
+        match opcode {
+            // LONG I LOAD, AUDIO PATTERN, PLANE SELECT, REGISTER RANGES, SCROLL UP
+            0xF000 | 0xF002 => return Platform::XoChip,
+            _ if opcode & 0xF0FF == 0xF001 || opcode & 0xF00E == 0x5002 || opcode & 0xFFF0 == 0x00D0 => {
+                return Platform::XoChip;
+            },
+            // SCROLLS, EXIT, HIRES, BIG FONT, FLAGS
+            0x00FB..=0x00FF => platform = Platform::Schip,
+            _ if opcode & 0xFFF0 == 0x00C0 => platform = Platform::Schip,
+            _ if opcode & 0xF0FF == 0xF030 || opcode & 0xF0FF == 0xF075 || opcode & 0xF0FF == 0xF085 => {
+                platform = Platform::Schip;
+            },
+            _ => {},
+        }
+    }
+    platform
+}
+
+// SMALL AND GOOD ENOUGH TO TELL ROMS APART, NO CRYPTOGRAPHIC PROPERTY NEEDED
+fn fnv1a(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in data {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
@@ -0,0 +1,147 @@
+// RENDERS MEMORY AS SPRITES THE WAY DXYN WOULD DRAW THEM, AND EXPORTS THEM AS PNG.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpriteFormat {
+    // 8 PIXELS WIDE, ONE BYTE PER ROW, 1 TO 15 ROWS
+    Chip8 { height: usize },
+    // SCHIP DXY0: 16x16 PIXELS, TWO BYTES PER ROW
+    Schip16,
+}
+
+impl SpriteFormat {
+    pub fn width(self) -> usize {
+        match self {
+            SpriteFormat::Chip8 { .. } => 8,
+            SpriteFormat::Schip16 => 16,
+        }
+    }
+
+    pub fn height(self) -> usize {
+        match self {
+            SpriteFormat::Chip8 { height } => height,
+            SpriteFormat::Schip16 => 16,
+        }
+    }
+
+    // BYTES ONE SPRITE TAKES IN MEMORY
+    pub fn size(self) -> usize {
+        self.width() / 8 * self.height()
+    }
+}
+
+// SPRITES LAID OUT IN A GRID WITH ONE PIXEL OF GAP BETWEEN THEM
+pub struct SpriteSheet {
+    pub width: usize,
+    pub height: usize,
+    // ROW BY ROW, TRUE FOR A LIT PIXEL
+    pub pixels: Vec<bool>,
+}
+
+impl SpriteSheet {
+    // count SPRITES READ ONE AFTER THE OTHER FROM start, columns OF THEM PER ROW.
+    // BYTES PAST THE END OF MEMORY WRAP AROUND, LIKE THE ADDRESSES DXYN READS.
+    pub fn render(memory: &[u8], start: usize, count: usize, format: SpriteFormat, columns: usize) -> SpriteSheet {
+        let columns = columns.clamp(1, count.max(1));
+        let rows = count.div_ceil(columns);
+        let cell_width = format.width() + 1;
+        let cell_height = format.height() + 1;
+        let width = (columns * cell_width).saturating_sub(1);
+        let height = (rows * cell_height).saturating_sub(1);
+        let mut pixels = vec![false; width * height];
+        let bytes_per_row = format.width() / 8;
+
+        for sprite in 0..count {
+            let left = (sprite % columns) * cell_width;
+            let top = (sprite / columns) * cell_height;
+            let address = start + sprite * format.size();
+            for y in 0..format.height() {
+                for x in 0..format.width() {
+                    let byte = memory[(address + y * bytes_per_row + x / 8) % memory.len()];
+                    if byte & (0x80 >> (x % 8)) != 0 {
+                        pixels[(top + y) * width + left + x] = true;
+                    }
+                }
+            }
+        }
+        SpriteSheet { width, height, pixels }
+    }
+
+    pub fn is_lit(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.width + x]
+    }
+
+    // 8-BIT GRAYSCALE PNG, LIT PIXELS WHITE, EACH SHEET PIXEL AS A scale x scale SQUARE
+    pub fn to_png(&self, scale: usize) -> Vec<u8> {
+        let scale = scale.max(1);
+        let width = self.width * scale;
+        let height = self.height * scale;
+        // EACH SCANLINE STARTS WITH ITS FILTER TYPE, 0 FOR NONE
+        let mut raw = Vec::with_capacity((width + 1) * height);
+        for y in 0..height {
+            raw.push(0);
+            for x in 0..width {
+                raw.push(if self.is_lit(x / scale, y / scale) { 0xFF } else { 0x00 });
+            }
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(width as u32).to_be_bytes());
+        header.extend_from_slice(&(height as u32).to_be_bytes());
+        // BIT DEPTH 8, GRAYSCALE, DEFLATE, STANDARD FILTERS, NO INTERLACE
+        header.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+// ZLIB STREAM OF UNCOMPRESSED DEFLATE BLOCKS: BIGGER FILES, BUT NO COMPRESSOR NEEDED
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xFFFF).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
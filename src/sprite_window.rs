@@ -0,0 +1,150 @@
+use std::fs;
+use chip8_emulator::chip8::Chip8;
+use chip8_emulator::sprite_sheet::{SpriteFormat, SpriteSheet};
+use minifb::{Key, KeyRepeat, Window, WindowOptions};
+use crate::osd::{Canvas, BOX_COLOR, TEXT_HEIGHT};
+
+// 8 x 4 SPRITES, EACH PIXEL AS A SCALE x SCALE SQUARE
+const COLUMNS: usize = 8;
+const ROWS: usize = 4;
+const SCALE: usize = 4;
+const MARGIN: usize = 8;
+const SHEET_TOP: usize = MARGIN + TEXT_HEIGHT + MARGIN;
+// ROOM FOR THE BIGGEST SPRITES, 16x16 PLUS ONE PIXEL OF GAP
+const WIDTH: usize = 2 * MARGIN + COLUMNS * 17 * SCALE;
+const HEIGHT: usize = SHEET_TOP + MARGIN + ROWS * 17 * SCALE;
+
+const DIM_COLOR: u32 = 0x808080;
+const LIT_COLOR: u32 = 0xFFFFFF;
+// FRAME AROUND THE SPRITE THE NEXT DXYN DRAWS
+const NEXT_DRAW_COLOR: u32 = 0xFFFF00;
+
+// SECOND WINDOW RENDERING MEMORY AS SPRITES. IT FOLLOWS I UNTIL THE ARROWS MOVE IT,
+// F FOLLOWS I AGAIN, + AND - CHANGE THE HEIGHT, S SWITCHES TO 16x16 SCHIP SPRITES AND
+// E EXPORTS THE SPRITES SHOWN AS sprites_XXXX.png.
+pub struct SpriteWindow {
+    window: Window,
+    buffer: Vec<u32>,
+    start: usize,
+    follow_i: bool,
+    format: SpriteFormat,
+    // LAST EXPORT, SHOWN IN THE HEADER
+    status: String,
+}
+
+impl SpriteWindow {
+    pub fn new() -> Option<SpriteWindow> {
+        let window = Window::new("Chip8 sprites", WIDTH, HEIGHT, WindowOptions::default())
+            .map_err(|e| eprintln!("{}", e))
+            .ok()?;
+        Some(SpriteWindow {
+            window,
+            buffer: vec![0; WIDTH * HEIGHT],
+            start: 0,
+            follow_i: true,
+            format: SpriteFormat::Chip8 { height: 5 },
+            status: String::new(),
+        })
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.window.is_open() && !self.window.is_key_down(Key::Escape)
+    }
+
+    // CALLED ONCE PER FRAME, AFTER THE FRAME RAN
+    pub fn update(&mut self, chip8: &Chip8) {
+        let memory = chip8.get_memory();
+        // THE SPRITE ABOUT TO BE DRAWN, WHEN THE NEXT INSTRUCTION IS DXYN
+        let next_draw = chip8.get_current_opcode().ok().filter(|opcode| opcode & 0xF000 == 0xD000).map(|opcode| {
+            match (opcode & 0xF) as usize {
+                0 => SpriteFormat::Schip16,
+                height => SpriteFormat::Chip8 { height },
+            }
+        });
+        self.handle_keys(memory.len());
+        if self.follow_i {
+            self.start = chip8.get_i() as usize % memory.len();
+            if let Some(format) = next_draw {
+                self.format = format;
+            }
+        }
+
+        let sheet = SpriteSheet::render(memory, self.start, COLUMNS * ROWS, self.format, COLUMNS);
+        if self.window.is_key_pressed(Key::E, KeyRepeat::No) {
+            let path = format!("sprites_{:04X}.png", self.start);
+            self.status = match fs::write(&path, sheet.to_png(SCALE)) {
+                Ok(()) => format!("SAVED {}", path),
+                Err(e) => e.to_string(),
+            };
+        }
+        self.draw(&sheet, next_draw.is_some() && self.follow_i);
+        self.window.update_with_buffer(&self.buffer).unwrap();
+    }
+
+    fn handle_keys(&mut self, size: usize) {
+        let step = self.format.size();
+        let moves = [
+            (Key::Left, size - 1),
+            (Key::Right, 1),
+            (Key::Up, size - step * COLUMNS),
+            (Key::Down, step * COLUMNS),
+            (Key::PageUp, size - step * COLUMNS * ROWS),
+            (Key::PageDown, step * COLUMNS * ROWS),
+        ];
+        for (key, offset) in moves {
+            if self.window.is_key_pressed(key, KeyRepeat::Yes) {
+                self.start = (self.start + offset) % size;
+                self.follow_i = false;
+            }
+        }
+        if self.window.is_key_pressed(Key::F, KeyRepeat::No) {
+            self.follow_i = true;
+        }
+        if self.window.is_key_pressed(Key::S, KeyRepeat::No) {
+            self.format = match self.format {
+                SpriteFormat::Schip16 => SpriteFormat::Chip8 { height: 5 },
+                SpriteFormat::Chip8 { .. } => SpriteFormat::Schip16,
+            };
+        }
+        if let SpriteFormat::Chip8 { height } = self.format {
+            if self.window.is_key_pressed(Key::Equal, KeyRepeat::Yes) || self.window.is_key_pressed(Key::NumPadPlus, KeyRepeat::Yes) {
+                self.format = SpriteFormat::Chip8 { height: (height + 1).min(15) };
+            }
+            if self.window.is_key_pressed(Key::Minus, KeyRepeat::Yes) || self.window.is_key_pressed(Key::NumPadMinus, KeyRepeat::Yes) {
+                self.format = SpriteFormat::Chip8 { height: (height - 1).max(1) };
+            }
+        }
+    }
+
+    fn draw(&mut self, sheet: &SpriteSheet, next_draw: bool) {
+        self.buffer.iter_mut().for_each(|pixel| *pixel = 0);
+        let mut canvas = Canvas::new(&mut self.buffer, WIDTH, HEIGHT);
+        let header = format!(
+            "{:04X}  {}x{}  {}  {}",
+            self.start,
+            self.format.width(),
+            self.format.height(),
+            if self.follow_i { "FOLLOWING I" } else { "F TO FOLLOW I" },
+            self.status,
+        );
+        canvas.draw_text(MARGIN, MARGIN, &header, DIM_COLOR);
+
+        let cell_width = (self.format.width() + 1) * SCALE;
+        let cell_height = (self.format.height() + 1) * SCALE;
+        for sprite in 0..COLUMNS * ROWS {
+            let x = MARGIN + (sprite % COLUMNS) * cell_width;
+            let y = SHEET_TOP + (sprite / COLUMNS) * cell_height;
+            if sprite == 0 && next_draw {
+                canvas.fill(x - 2, y - 2, cell_width, cell_height, NEXT_DRAW_COLOR);
+            }
+            canvas.fill(x, y, self.format.width() * SCALE, self.format.height() * SCALE, BOX_COLOR);
+        }
+        for y in 0..sheet.height {
+            for x in 0..sheet.width {
+                if sheet.is_lit(x, y) {
+                    canvas.fill(MARGIN + x * SCALE, SHEET_TOP + y * SCALE, SCALE, SCALE, LIT_COLOR);
+                }
+            }
+        }
+    }
+}
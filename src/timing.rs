@@ -0,0 +1,66 @@
+// COSMAC VIP INSTRUCTION COSTS, IN 1802 MACHINE CYCLES. THEY ARE ESTIMATES, NOT MEASUREMENTS:
+// THEY FOLLOW THE SHAPE OF THE INTERPRETER'S ROUTINES (A FIXED FETCH AND DECODE, THEN A LOOP
+// PER SPRITE ROW, PER BCD UNIT OR PER REGISTER COPIED) SO SLOW INSTRUCTIONS STAY SLOW, BUT A
+// ROM TIMED ON A REAL VIP CAN STILL DRIFT. THE VIP RUNS AT 1.7609 MHZ WITH 8 CLOCK PULSES PER
+// MACHINE CYCLE, SO A 60 HZ FRAME IS 3668 MACHINE CYCLES. THE CDP1861 DISPLAY STEALS 1024 OF
+// THEM FOR ITS DMA (128 LINES OF 8 BYTES) AND THE INTERRUPT ROUTINE ROUGHLY 29 MORE, THE
+// INTERPRETER GETS THE REST.
+pub const VIP_CYCLES_PER_FRAME: u32 = 3668 - 1024 - 29;
+
+// FETCHING AND DECODING, PAID BY EVERY INSTRUCTION
+const FETCH_CYCLES: u32 = 68;
+
+// WHAT THE CPU STATE LOOKED LIKE AROUND ONE INSTRUCTION, ENOUGH TO PRICE IT
+pub struct Executed {
+    pub opcode: u16,
+    // VX AND V0 BEFORE THE INSTRUCTION RAN
+    pub vx: u8,
+    pub v0: u8,
+    // A SKIP INSTRUCTION JUMPED OVER THE NEXT ONE
+    pub skipped: bool,
+}
+
+pub fn vip_cycles(executed: &Executed) -> u32 {
+    let opcode = executed.opcode;
+    let x = (opcode & 0x0F00) >> 8;
+    let n = (opcode & 0x000F) as u32;
+    let nnn = opcode & 0x0FFF;
+    let skip = if executed.skipped { 4 } else { 0 };
+    let cost = match opcode >> 12 {
+        0x0 if opcode == 0x00E0 => 3078,
+        0x0 => 10,
+        0x1 => 12,
+        0x2 => 26,
+        0x3 | 0x4 => 10 + skip,
+        0x5 | 0x9 => 14 + skip,
+        0x6 => 6,
+        0x7 => 10,
+        0x8 if n == 0 => 12,
+        0x8 => 44,
+        0xA => 12,
+        0xB => {
+            // CROSSING A 256 BYTE PAGE TAKES ONE MORE CARRY
+            let target = nnn + executed.v0 as u16;
+            if target >> 8 != nnn >> 8 { 24 } else { 22 }
+        },
+        0xC => 36,
+        0xD => {
+            // EACH ROW IS SHIFTED INTO PLACE ONE BIT AT A TIME
+            26 + n * (46 + 8 * (executed.vx as u32 % 8))
+        },
+        0xE => 14 + skip,
+        _ => match opcode & 0xFF {
+            0x1E | 0x29 => 16,
+            0x33 => {
+                // ONE LOOP PER UNIT COUNTED IN EACH DIGIT
+                let vx = executed.vx as u32;
+                80 + 16 * (vx / 100 + (vx / 10) % 10 + vx % 10)
+            },
+            0x55 | 0x65 => 14 + 14 * (x as u32 + 1),
+            // FX0A IS CHARGED FOR EVERY POLL WHILE IT WAITS
+            0x0A => 18,
+            _ => 10,
+        },
+    };
+    FETCH_CYCLES + cost
+}
@@ -0,0 +1,20 @@
+use chip8_emulator::{Chip8Error, Config, Machine};
+
+// SHARED BY tests/decode_cache.rs AND THE BENCHES, WHICH INCLUDE IT WITH #[path]
+
+pub fn machine(config: Config, rom: &[u8]) -> Machine {
+    let mut machine = Machine::with_config(config);
+    machine.set_random_seed(1);
+    machine.load_rom(rom).unwrap();
+    machine
+}
+
+// STOPS AT THE FIRST FRAME THAT FAILS
+pub fn run_frames(machine: &mut Machine, frames: usize) -> Result<(), Chip8Error> {
+    for frame in 0..frames {
+        // KEY 5 FOR A FEW FRAMES NOW AND THEN
+        machine.change_keys_pressed(if frame % 50 < 3 { 1 << 5 } else { 0 });
+        machine.run_frame()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+// RUNS ROMS HEADLESSLY FOR A FIXED NUMBER OF FRAMES AND COMPARES THE SCREEN TO GOLDEN IMAGES.
+//
+// GOLDEN IMAGES ARE TEXT FILES IN tests/golden, ONE LINE PER ROW, '#' FOR A LIT PIXEL.
+// RUN WITH UPDATE_GOLDEN=1 TO WRITE MISSING OR CHANGED IMAGES, THEN REVIEW THEM BEFORE COMMITTING.
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chip8_emulator::display::{HEIGHT_SCREEN, WIDTH_SCREEN};
+use chip8_emulator::{Chip8, Config, Quirks};
+
+const PRESETS: [&str; 3] = ["legacy", "chip8", "schip"];
+
+struct Case {
+    rom: &'static str,
+    frames: usize,
+    // (FIRST FRAME, LAST FRAME, KEYPAD MASK) HELD DURING THE RUN
+    keys: &'static [(usize, usize, u16)],
+}
+
+// SELF-CHECKING TEST ROMS BUILT FROM THE OCTO SOURCES IN tests/roms (SEE tests/roms/README.md),
+// THEIR GOLDEN IMAGES ARE THE PASS SCREENS THE SOURCES DESCRIBE
+const SUITE: [Case; 5] = [
+    Case { rom: "tests/roms/ibm-logo.ch8", frames: 60, keys: &[] },
+    Case { rom: "tests/roms/opcodes.ch8", frames: 60, keys: &[] },
+    Case { rom: "tests/roms/flags.ch8", frames: 60, keys: &[] },
+    Case { rom: "tests/roms/quirks.ch8", frames: 60, keys: &[] },
+    Case { rom: "tests/roms/keypad.ch8", frames: 120, keys: &[(20, 40, 1 << 0xA), (60, 80, 1 << 0x1 | 1 << 0xF)] },
+];
+
+// PUBLIC DOMAIN GAMES SHIPPED IN data/, USED AS REGRESSION IMAGES
+const GAMES: [Case; 8] = [
+    Case { rom: "data/BLINKY", frames: 300, keys: &[] },
+    Case { rom: "data/BRIX", frames: 300, keys: &[(60, 120, 1 << 4)] },
+    Case { rom: "data/INVADERS", frames: 300, keys: &[(30, 40, 1 << 5)] },
+    Case { rom: "data/MAZE", frames: 300, keys: &[] },
+    Case { rom: "data/PONG", frames: 300, keys: &[(60, 90, 1 << 1)] },
+    Case { rom: "data/TETRIS", frames: 300, keys: &[(30, 40, 1 << 6)] },
+    Case { rom: "data/UFO", frames: 300, keys: &[(60, 62, 1 << 5)] },
+    Case { rom: "data/VBRIX", frames: 300, keys: &[(30, 40, 1 << 7)] },
+];
+
+fn root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn run(case: &Case, rom: &[u8], preset: &str) -> String {
+    let config = Config {
+        quirks: Quirks::from_name(preset).unwrap(),
+        ..Config::default()
+    };
+    let mut chip8 = Chip8::with_config(config);
+    chip8.load_rom(rom).unwrap();
+    for frame in 0..case.frames {
+        let keys = case
+            .keys
+            .iter()
+            .filter(|(first, last, _)| (*first..*last).contains(&frame))
+            .fold(0, |mask, (_, _, keys)| mask | keys);
+        chip8.change_keys_pressed(keys);
+        chip8.run_frame().unwrap();
+    }
+    render(chip8.get_display())
+}
+
+fn render(screen: &[u8]) -> String {
+    let mut image = String::with_capacity((WIDTH_SCREEN + 1) * HEIGHT_SCREEN);
+    for row in screen.chunks(WIDTH_SCREEN) {
+        image.extend(row.iter().map(|pixel| if *pixel == 1 { '#' } else { '.' }));
+        image.push('\n');
+    }
+    image
+}
+
+fn golden_path(rom: &str, preset: &str) -> PathBuf {
+    let name = Path::new(rom).file_stem().unwrap().to_str().unwrap();
+    root().join("tests/golden").join(format!("{}-{}.txt", name, preset))
+}
+
+// RETURNS A DESCRIPTION OF EVERY MISMATCH INSTEAD OF STOPPING AT THE FIRST ONE
+fn check(cases: &[Case]) -> Vec<String> {
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+    let mut failures = Vec::new();
+    for case in cases {
+        let Ok(rom) = fs::read(root().join(case.rom)) else {
+            failures.push(format!("{}: rom is missing", case.rom));
+            continue;
+        };
+        for preset in PRESETS {
+            let actual = run(case, &rom, preset);
+            let path = golden_path(case.rom, preset);
+            match fs::read_to_string(&path) {
+                Ok(expected) if expected == actual => {}
+                _ if update => fs::write(&path, &actual).unwrap(),
+                Ok(expected) => failures.push(format!(
+                    "{} ({}) differs from {}\nexpected:\n{}actual:\n{}",
+                    case.rom, preset, path.display(), expected, actual
+                )),
+                Err(_) => failures.push(format!(
+                    "{} ({}): no golden image at {}, run with UPDATE_GOLDEN=1\n{}",
+                    case.rom, preset, path.display(), actual
+                )),
+            }
+        }
+    }
+    failures
+}
+
+#[test]
+fn test_suite_roms_match_golden_images() {
+    let failures = check(&SUITE);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn games_match_golden_images() {
+    let failures = check(&GAMES);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
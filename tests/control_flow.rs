@@ -0,0 +1,65 @@
+use chip8_emulator::control_flow::{ControlFlowGraph, Edge, EdgeKind};
+
+// A SKIP OVER A CALL, THEN A WRITE OVER THE SKIP AND A COMPUTED JUMP
+const PROGRAM: [u8; 20] = [
+    0x60, 0x01, 0x30, 0x01, 0x22, 0x10, // 200: V0 = 1, SKIP IF V0 == 1, CALL 210
+    0xA2, 0x02, 0xF0, 0x55, 0xB3, 0x00, // 206: I = 202, STORE V0, JUMP 300 + V0
+    0x00, 0x00, 0x00, 0x00, // 20C: NEVER REACHED
+    0x00, 0xEE, 0x00, 0x00, // 210: RETURN
+];
+
+#[test]
+fn blocks_end_at_skips_calls_and_jumps() {
+    let graph = ControlFlowGraph::build(&PROGRAM);
+    let starts: Vec<u16> = graph.blocks.iter().map(|block| block.start).collect();
+    assert_eq!(starts, vec![0x200, 0x204, 0x206, 0x210]);
+
+    let entry = graph.get_block(0x200).unwrap();
+    assert_eq!(entry.instructions, vec![(0x200, 0x6001), (0x202, 0x3001)]);
+    assert_eq!(
+        entry.successors,
+        vec![Edge { target: 0x204, kind: EdgeKind::NoSkip }, Edge { target: 0x206, kind: EdgeKind::Skip }]
+    );
+    assert_eq!(
+        graph.get_block(0x204).unwrap().successors,
+        vec![Edge { target: 0x210, kind: EdgeKind::Call }, Edge { target: 0x206, kind: EdgeKind::Return }]
+    );
+    assert!(graph.get_block(0x206).unwrap().successors.is_empty());
+    assert!(graph.get_block(0x210).unwrap().successors.is_empty());
+    assert_eq!(graph.subroutines, vec![0x210]);
+    assert_eq!(graph.unresolved, vec![0x20A]);
+}
+
+#[test]
+fn writes_over_code_are_flagged() {
+    let graph = ControlFlowGraph::build(&PROGRAM);
+    assert_eq!(graph.warnings.len(), 1);
+    assert_eq!(graph.warnings[0].address, 0x208);
+    assert_eq!(graph.warnings[0].message, "LD [I], V0 writes 0202-0202, which holds code");
+}
+
+#[test]
+fn writes_after_i_moved_are_not_flagged() {
+    let program = [
+        0xA2, 0x00, 0xF0, 0x29, 0xF0, 0x55, // 200: I = 200, I = FONT OF V0, STORE V0
+        0xA2, 0x00, 0xF0, 0x65, 0xF0, 0x55, // 206: I = 200, LOAD V0, STORE V0
+        0xA2, 0x00, 0xF0, 0x55, 0xF0, 0x55, // 20C: I = 200, STORE V0, STORE V0
+        0x12, 0x12, // 212: JUMP 212
+    ];
+    let graph = ControlFlowGraph::build(&program);
+    let flagged: Vec<u16> = graph.warnings.iter().map(|warning| warning.address).collect();
+    assert_eq!(flagged, vec![0x20E]);
+}
+
+#[test]
+fn graph_is_exported_as_dot_and_json() {
+    let graph = ControlFlowGraph::build(&PROGRAM);
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph rom {"));
+    assert!(dot.contains("b0200 [label=\"0200  LD V0, 0x01\\l0202  SE V0, 0x01\\l\"];"));
+    assert!(dot.contains("b0204 -> b0206 [label=\"return\", style=dashed];"));
+
+    let json = graph.to_json();
+    assert!(json.starts_with("{\"entry\": 512, \"blocks\": [{\"start\": 512, \"instructions\": [{\"address\": 512, \"opcode\": 24577, \"text\": \"LD V0, 0x01\"}"));
+    assert!(json.contains("\"subroutines\": [528], \"unresolved\": [522]"));
+}
@@ -0,0 +1,75 @@
+use chip8_emulator::coverage::{READ, WRITTEN};
+use chip8_emulator::{disasm, Machine};
+
+fn step(machine: &mut Machine, count: usize) {
+    for _ in 0..count {
+        machine.run_instruction().unwrap();
+    }
+}
+
+// SKIPS OVER A CLS, WRITES VA AS BCD AT 0x20E AND DRAWS ITS FIRST BYTE
+const PROGRAM: [u8; 18] = [
+    0x60, 0x05, 0x30, 0x05, 0x00, 0xE0, // 200: V0 = 5, SKIP IF V0 == 5, CLS
+    0xA2, 0x0E, 0xF2, 0x33, 0xD0, 0x01, // 206: I = 20E, BCD OF V2, DRAW 1 ROW
+    0x12, 0x0C, // 20C: JUMP 20C
+    0x00, 0x00, 0x00, 0xFF, // 20E: DATA
+];
+
+fn covered_run() -> Machine {
+    let mut machine = Machine::new();
+    machine.load_rom(&PROGRAM).unwrap();
+    machine.start_coverage();
+    step(&mut machine, 7);
+    machine
+}
+
+#[test]
+fn executed_read_and_written_bytes_are_tracked() {
+    let machine = covered_run();
+    let coverage = machine.get_coverage().unwrap();
+    assert!(coverage.is_executed(0x202) && coverage.is_executed(0x203));
+    assert!(!coverage.is_executed(0x204));
+    assert_eq!(coverage.get_flags(0x20E), READ | WRITTEN);
+    assert_eq!(coverage.get_flags(0x210), WRITTEN);
+    assert_eq!(coverage.untouched(0x200, 0x212), vec![(0x204, 0x205), (0x211, 0x211)]);
+    assert_eq!(coverage.partial_branches(0x200, 0x212), vec![0x202]);
+}
+
+#[test]
+fn report_and_listing_show_what_never_ran() {
+    let machine = covered_run();
+    let coverage = machine.get_coverage().unwrap();
+    let report = coverage.report(machine.get_memory(), 0x200, 0x212);
+    assert!(report.contains("0204-0205      2 bytes"));
+    assert!(report.contains("0202  SE V0, 0x05      always skipped"));
+
+    let listing = coverage.listing(machine.get_memory(), 0x200, 0x212);
+    let lines: Vec<&str> = listing.lines().collect();
+    assert_eq!(lines.len(), 9);
+    assert_eq!(lines[1], "0202  3005  SE V0, 0x05       X--  always skipped");
+    assert_eq!(lines[2], "0204  00E0  CLS               ---");
+    assert_eq!(lines[7], "020E  0000  SYS 0x000         -RW");
+}
+
+#[test]
+fn opcodes_are_disassembled() {
+    assert_eq!(disasm::disassemble(0x00EE), "RET");
+    assert_eq!(disasm::disassemble(0x8AB6), "SHR VA, VB");
+    assert_eq!(disasm::disassemble(0xD125), "DRW V1, V2, 5");
+    assert_eq!(disasm::disassemble(0xFA65), "LD VA, [I]");
+    assert_eq!(disasm::disassemble(0xE0FF), "DW 0xE0FF");
+}
+
+#[test]
+fn skips_and_instructions_are_decoded_like_the_cpu() {
+    for skip in [0x3A01, 0x4A01, 0x5AB0, 0x5AB3, 0x9AB0, 0xEA9E, 0xEAA1] {
+        assert!(disasm::is_skip(skip), "{:04X}", skip);
+    }
+    for other in [0x1200, 0x6A01, 0xEA9F, 0xFA0A] {
+        assert!(!disasm::is_skip(other), "{:04X}", other);
+    }
+    assert!(disasm::is_instruction(0x0AEE));
+    assert!(!disasm::is_instruction(0x0123));
+    assert!(!disasm::is_instruction(0x8AB8));
+    assert!(!disasm::is_instruction(0xFA99));
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use std::fs;
+use chip8_emulator::{Addressing, Config, Machine, Quirks};
+
+fn run(config: Config, rom: &[u8], frames: usize) -> Machine {
+    let mut machine = common::machine(config, rom);
+    common::run_frames(&mut machine, frames).unwrap();
+    machine
+}
+
+// THE SAME FRAMES WITH AND WITHOUT THE DECODE CACHE, THE MACHINES MUST NOT DIFFER
+fn assert_same_runs(config: Config, rom: &[u8], frames: usize) -> Machine {
+    let uncached = run(Config { decode_cache: false, ..config }, rom, frames);
+    let cached = run(Config { decode_cache: true, ..config }, rom, frames);
+    assert!(uncached.save_state() == cached.save_state(), "{:?} run differs", config);
+    assert_eq!(uncached.get_instructions_run(), cached.get_instructions_run());
+    cached
+}
+
+#[test]
+fn self_modifying_code_is_decoded_again() {
+    let rom = [
+        0xA2, 0x10, 0x60, 0x73, 0x61, 0x01, // 200: I = 210, V0 = 73, V1 = 01
+        0x74, 0x01, 0x44, 0x08, // 206: V4 += 1, SKIP IF V4 != 8
+        0xF1, 0x55, // 20A: WRITE 7301 (V3 += 1) AT 210
+        0x65, 0x00, 0x65, 0x00, // 20C: V5 = 0, V5 = 0
+        0x72, 0x01, 0x12, 0x06, // 210: V2 += 1, JUMP 206
+    ];
+    let machine = assert_same_runs(Config::default(), &rom, 10);
+    assert_eq!(machine.get_register(2), 7);
+    assert!(machine.get_register(3) > 0);
+}
+
+#[test]
+fn memory_written_between_frames_is_decoded_again() {
+    // 200: V2 += 1, JUMP 200
+    let rom = [0x72, 0x01, 0x12, 0x00];
+    let mut machine = run(Config::default(), &rom, 1);
+    assert_eq!(machine.get_register(2), 5);
+    // V3 += 1 INSTEAD
+    machine.write_memory(0x200, 0x73).unwrap();
+    machine.run_frame().unwrap();
+    assert_eq!(machine.get_register(2), 5);
+    assert_eq!(machine.get_register(3), 5);
+}
+
+#[test]
+fn failing_instruction_is_not_counted() {
+    // 5 INSTRUCTIONS, THEN AN UNKNOWN OPCODE
+    let rom = [0x60, 0x01, 0x61, 0x01, 0x62, 0x01, 0x63, 0x01, 0x64, 0x01, 0xFF, 0xFF];
+    for decode_cache in [false, true] {
+        let mut machine = common::machine(Config { decode_cache, ..Config::default() }, &rom);
+        assert!(machine.run_frame().is_err());
+        assert_eq!(machine.get_instructions_run(), 5);
+    }
+}
+
+#[test]
+fn bundled_roms_run_identically() {
+    let configs = [
+        Config::default(),
+        Config { quirks: Quirks::chip8(), instructions_per_frame: 1000, ..Config::default() },
+        Config { quirks: Quirks::schip(), addressing: Addressing::Wrap16, ..Config::default() },
+    ];
+    for entry in fs::read_dir("data").unwrap() {
+        let rom = fs::read(entry.unwrap().path()).unwrap();
+        for config in configs {
+            assert_same_runs(config, &rom, 300);
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::fs;
+use chip8_emulator::decompiler;
+
+// ASSEMBLES THE PART OF OCTO THE DECOMPILER WRITES, TO CHECK ITS SOURCE GIVES THE ROM BACK
+fn assemble(source: &str) -> Vec<u8> {
+    let lines: Vec<Vec<&str>> = source
+        .lines()
+        .map(|line| line.split('#').next().unwrap().split_whitespace().collect())
+        .filter(|tokens: &Vec<&str>| !tokens.is_empty())
+        .collect();
+
+    // FIRST PASS: WHERE THE LABELS ARE
+    let mut labels = HashMap::new();
+    let mut aliases = HashMap::new();
+    let mut address = 0x200;
+    for tokens in &lines {
+        match tokens[0] {
+            ":" => {
+                labels.insert(tokens[1].to_string(), address);
+            },
+            ":alias" => {
+                aliases.insert(tokens[1].to_string(), tokens[2].to_string());
+            },
+            "loop" | "end" => {},
+            "again" | "else" => address += 2,
+            "while" => address += 4,
+            "if" => address += 4,
+            first if first.starts_with("0x") => address += tokens.len() as u16,
+            _ => address += 2,
+        }
+    }
+
+    // SECOND PASS
+    let assembler = Assembler { labels, aliases };
+    let mut rom: Vec<u8> = Vec::new();
+    // (LOOP START, WHILE JUMPS TO PATCH) AND (IF JUMP TO PATCH)
+    let mut loops: Vec<(u16, Vec<usize>)> = Vec::new();
+    let mut ifs: Vec<usize> = Vec::new();
+    let here = |rom: &Vec<u8>| 0x200 + rom.len() as u16;
+    let patch = |rom: &mut Vec<u8>, at: usize, target: u16| {
+        rom[at] = 0x10 | (target >> 8) as u8;
+        rom[at + 1] = target as u8;
+    };
+    for tokens in &lines {
+        match tokens[0] {
+            ":" | ":alias" => {},
+            "loop" => loops.push((here(&rom), Vec::new())),
+            "again" => {
+                let (start, whiles) = loops.pop().unwrap();
+                push(&mut rom, 0x1000 | start);
+                let after = here(&rom);
+                for at in whiles {
+                    patch(&mut rom, at, after);
+                }
+            },
+            "while" => {
+                push(&mut rom, assembler.skip(&tokens[1..], false));
+                loops.last_mut().unwrap().1.push(rom.len());
+                push(&mut rom, 0x1000);
+            },
+            "if" if tokens.last() == Some(&"begin") => {
+                push(&mut rom, assembler.skip(&tokens[1..tokens.len() - 1], false));
+                ifs.push(rom.len());
+                push(&mut rom, 0x1000);
+            },
+            "if" => {
+                let then = tokens.iter().position(|token| *token == "then").unwrap();
+                push(&mut rom, assembler.skip(&tokens[1..then], true));
+                push(&mut rom, assembler.statement(&tokens[then + 1..]));
+            },
+            "else" => {
+                let begin = ifs.pop().unwrap();
+                ifs.push(rom.len());
+                push(&mut rom, 0x1000);
+                let after = here(&rom);
+                patch(&mut rom, begin, after);
+            },
+            "end" => {
+                let at = ifs.pop().unwrap();
+                let after = here(&rom);
+                patch(&mut rom, at, after);
+            },
+            first if first.starts_with("0x") => rom.extend(tokens.iter().map(|token| assembler.value(token) as u8)),
+            _ => push(&mut rom, assembler.statement(tokens)),
+        }
+    }
+    rom
+}
+
+fn push(rom: &mut Vec<u8>, opcode: u16) {
+    rom.extend_from_slice(&opcode.to_be_bytes());
+}
+
+struct Assembler {
+    labels: HashMap<String, u16>,
+    aliases: HashMap<String, String>,
+}
+
+impl Assembler {
+    fn value(&self, token: &str) -> u16 {
+        match token.strip_prefix("0x") {
+            Some(hex) => u16::from_str_radix(hex, 16).unwrap(),
+            None => self.labels[token],
+        }
+    }
+
+    fn register(&self, token: &str) -> u16 {
+        let name = self.aliases.get(token).map_or(token, |name| name.as_str());
+        u16::from_str_radix(name.strip_prefix('v').unwrap(), 16).unwrap() << 8
+    }
+
+    // THE SKIP SKIPPING WHEN THE CONDITION HOLDS, OR WHEN IT DOES NOT WITH inverted
+    fn skip(&self, condition: &[&str], inverted: bool) -> u16 {
+        let x = self.register(condition[0]);
+        let operator = match (condition[1], inverted) {
+            ("==", true) => "!=",
+            ("!=", true) => "==",
+            ("key", true) => "-key",
+            ("-key", true) => "key",
+            (operator, _) => operator,
+        };
+        match (operator, condition.get(2)) {
+            ("key", _) => 0xE09E | x,
+            ("-key", _) => 0xE0A1 | x,
+            ("==", Some(y)) if y.starts_with('v') || self.aliases.contains_key(*y) => 0x5000 | x | self.register(y) >> 4,
+            ("!=", Some(y)) if y.starts_with('v') || self.aliases.contains_key(*y) => 0x9000 | x | self.register(y) >> 4,
+            ("==", Some(nn)) => 0x3000 | x | self.value(nn),
+            ("!=", Some(nn)) => 0x4000 | x | self.value(nn),
+            _ => panic!("bad condition {:?}", condition),
+        }
+    }
+
+    fn statement(&self, tokens: &[&str]) -> u16 {
+        let is_register = |token: &str| token.starts_with('v') && token.len() == 2 || self.aliases.contains_key(token);
+        match tokens {
+            ["clear"] => 0x00E0,
+            ["return"] => 0x00EE,
+            ["jump", target] => 0x1000 | self.value(target),
+            ["jump0", target] => 0xB000 | self.value(target),
+            [":call", target] => 0x2000 | self.value(target),
+            [label] => 0x2000 | self.value(label),
+            ["i", ":=", "hex", x] => 0xF029 | self.register(x),
+            ["i", ":=", target] => 0xA000 | self.value(target),
+            ["i", "+=", x] => 0xF01E | self.register(x),
+            ["delay", ":=", x] => 0xF015 | self.register(x),
+            ["buzzer", ":=", x] => 0xF018 | self.register(x),
+            ["bcd", x] => 0xF033 | self.register(x),
+            ["save", x] => 0xF055 | self.register(x),
+            ["load", x] => 0xF065 | self.register(x),
+            ["sprite", x, y, n] => 0xD000 | self.register(x) | self.register(y) >> 4 | n.parse::<u16>().unwrap(),
+            [x, ":=", "delay"] => 0xF007 | self.register(x),
+            [x, ":=", "key"] => 0xF00A | self.register(x),
+            [x, ":=", "random", nn] => 0xC000 | self.register(x) | self.value(nn),
+            [x, operator, y] if is_register(y) => {
+                let n = match *operator {
+                    ":=" => 0x0,
+                    "|=" => 0x1,
+                    "&=" => 0x2,
+                    "^=" => 0x3,
+                    "+=" => 0x4,
+                    "-=" => 0x5,
+                    ">>=" => 0x6,
+                    "=-" => 0x7,
+                    "<<=" => 0xE,
+                    _ => panic!("bad operator {}", operator),
+                };
+                0x8000 | self.register(x) | self.register(y) >> 4 | n
+            },
+            [x, ":=", nn] => 0x6000 | self.register(x) | self.value(nn),
+            [x, "+=", nn] => 0x7000 | self.register(x) | self.value(nn),
+            _ => panic!("bad statement {:?}", tokens),
+        }
+    }
+}
+
+#[test]
+fn structures_are_recovered() {
+    let rom = [
+        0x60, 0x00, // 200: V0 = 0
+        0x40, 0x05, 0x12, 0x0A, // 202: LOOP: SKIP IF V0 != 5, JUMP 20A
+        0x70, 0x01, 0x12, 0x02, // 206: V0 += 1, JUMP 202
+        0xF1, 0x0A, 0x41, 0x00, 0x12, 0x14, // 20A: V1 = KEY, SKIP IF V1 != 0, JUMP 214
+        0x62, 0x01, 0x12, 0x16, // 210: V2 = 1, JUMP 216
+        0x62, 0x02, // 214: V2 = 2
+        0x31, 0x03, 0x63, 0x07, // 216: SKIP IF V1 == 3, V3 = 7
+        0x12, 0x1A, // 21A: JUMP 21A
+    ];
+    let source = decompiler::decompile(&rom);
+    let expected = "\
+# DECOMPILED FROM A 28 BYTE ROM
+:alias keycode v1
+
+: main
+	v0 := 0x00
+	loop
+		while v0 != 0x05
+		v0 += 0x01
+	again
+	keycode := key
+	if keycode != 0x00 begin
+		v2 := 0x01
+	else
+		v2 := 0x02
+	end
+	if keycode != 0x03 then v3 := 0x07
+	loop
+	again
+";
+    assert_eq!(source, expected);
+    assert_eq!(assemble(&source), rom);
+}
+
+#[test]
+fn bundled_roms_assemble_back() {
+    for entry in fs::read_dir("data").unwrap() {
+        let path = entry.unwrap().path();
+        let rom = fs::read(&path).unwrap();
+        let source = decompiler::decompile(&rom);
+        let assembled = assemble(&source);
+        let first_difference = (0..rom.len().max(assembled.len())).find(|index| rom.get(*index) != assembled.get(*index));
+        assert_eq!(first_difference, None, "{} does not assemble back", path.display());
+    }
+}
@@ -0,0 +1,52 @@
+use std::fs;
+use chip8_emulator::{Config, Machine, Timing};
+
+// RUNS THE SAME FRAMES WITH AND WITHOUT IDLE LOOP SKIPPING, THE MACHINES MUST NOT DIFFER
+fn assert_same_runs(rom: &[u8], timing: Timing, frames: usize) {
+    let machines: Vec<Machine> = [false, true]
+        .into_iter()
+        .map(|skip_idle_loops| {
+            let mut machine = Machine::with_config(Config { timing, skip_idle_loops, ..Config::default() });
+            machine.set_random_seed(1);
+            machine.load_rom(rom).unwrap();
+            for frame in 0..frames {
+                // KEY 5 FOR A FEW FRAMES NOW AND THEN
+                machine.change_keys_pressed(if frame % 50 < 3 { 1 << 5 } else { 0 });
+                machine.run_frame().unwrap();
+            }
+            machine
+        })
+        .collect();
+    assert!(machines[0].save_state() == machines[1].save_state(), "{:?} run differs", timing);
+    assert_eq!(machines[0].get_instructions_run(), machines[1].get_instructions_run());
+}
+
+#[test]
+fn delay_timer_waits_are_skipped_identically() {
+    // 200: V1 = 20, DT = V1, V0 = DT, SKIP IF V0 == 0, JUMP 204, V2 += 1, JUMP 200
+    let rom = [0x61, 0x14, 0xF1, 0x15, 0xF0, 0x07, 0x30, 0x00, 0x12, 0x04, 0x72, 0x01, 0x12, 0x00];
+    assert_same_runs(&rom, Timing::Fixed, 200);
+    assert_same_runs(&rom, Timing::Vip, 200);
+
+    // THE SAME WAIT WRITTEN AS 4XNN, LOOPING WHILE THE TIMER IS STILL ABOVE 1
+    let rom = [0x61, 0x14, 0xF1, 0x15, 0xF0, 0x07, 0x40, 0x01, 0x12, 0x04, 0x72, 0x01, 0x12, 0x00];
+    assert_same_runs(&rom, Timing::Fixed, 200);
+    assert_same_runs(&rom, Timing::Vip, 200);
+}
+
+#[test]
+fn key_waits_are_skipped_identically() {
+    // 200: V0 = KEY, V2 += V0, JUMP 200
+    let rom = [0xF0, 0x0A, 0x82, 0x04, 0x12, 0x00];
+    assert_same_runs(&rom, Timing::Fixed, 200);
+    assert_same_runs(&rom, Timing::Vip, 200);
+}
+
+#[test]
+fn bundled_roms_run_identically() {
+    for entry in fs::read_dir("data").unwrap() {
+        let rom = fs::read(entry.unwrap().path()).unwrap();
+        assert_same_runs(&rom, Timing::Fixed, 300);
+        assert_same_runs(&rom, Timing::Vip, 300);
+    }
+}
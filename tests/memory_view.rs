@@ -0,0 +1,21 @@
+use chip8_emulator::memory_view::MemoryView;
+use chip8_emulator::Machine;
+
+#[test]
+fn dump_marks_changes_pc_and_font() {
+    let mut machine = Machine::builder().i(0x300).program(&[0x00E0, 0xA300]).build();
+    let mut view = MemoryView::new(&machine);
+    assert_eq!(
+        view.dump_row(&machine, 0x200),
+        "0200  00 E0 A3 00 00 00 00 00 00 00 00 00 00 00 00 00  |................|  PC"
+    );
+    assert!(view.dump_row(&machine, 0x000).ends_with("  FONT"));
+
+    machine.write_memory(0x301, 0x41).unwrap();
+    assert_eq!(
+        view.dump_row(&machine, 0x300),
+        "0300  00 41*00 00 00 00 00 00 00 00 00 00 00 00 00 00  |.A..............|  I"
+    );
+    view.snapshot(&machine);
+    assert!(!view.has_changed(&machine, 0x301));
+}
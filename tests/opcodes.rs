@@ -0,0 +1,548 @@
+use chip8_emulator::{Addressing, CallFrame, Chip8Error, Config, Machine, Quirks, Stack, Timing};
+
+// RUNS THE GIVEN NUMBER OF INSTRUCTIONS
+fn step(machine: &mut Machine, count: usize) {
+    for _ in 0..count {
+        machine.run_instruction().unwrap();
+    }
+}
+
+fn lit_pixels(machine: &Machine) -> usize {
+    machine.get_display().iter().filter(|pixel| **pixel == 1).count()
+}
+
+#[test]
+fn clear_screen_00e0() {
+    let mut machine = Machine::builder()
+        .i(0x300)
+        .mem(0x300, &[0xFF])
+        .program(&[0xD001, 0x00E0])
+        .build();
+    step(&mut machine, 1);
+    assert_eq!(lit_pixels(&machine), 8);
+    step(&mut machine, 1);
+    assert_eq!(lit_pixels(&machine), 0);
+    assert_eq!(machine.get_pc(), 0x204);
+}
+
+#[test]
+fn call_2nnn_and_return_00ee() {
+    let mut machine = Machine::builder().program(&[0x2300]).mem(0x300, &[0x00, 0xEE]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0x300);
+    assert_eq!(machine.get_stack(), &[0x202]);
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0x202);
+    assert!(machine.get_stack().is_empty());
+}
+
+#[test]
+fn jump_1nnn() {
+    let mut machine = Machine::builder().program(&[0x1ABC]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0xABC);
+}
+
+#[test]
+fn skips_3xnn_4xnn_5xy0_9xy0() {
+    let cases = [
+        (0x3142, 0x42, 0x00, 0x204),
+        (0x3142, 0x41, 0x00, 0x202),
+        (0x4142, 0x41, 0x00, 0x204),
+        (0x4142, 0x42, 0x00, 0x202),
+        (0x5120, 0x07, 0x07, 0x204),
+        (0x5120, 0x07, 0x08, 0x202),
+        (0x9120, 0x07, 0x08, 0x204),
+        (0x9120, 0x07, 0x07, 0x202),
+    ];
+    for (opcode, v1, v2, pc) in cases {
+        let mut machine = Machine::builder().reg(1, v1).reg(2, v2).program(&[opcode]).build();
+        step(&mut machine, 1);
+        assert_eq!(machine.get_pc(), pc, "{:04X} with V1={:02X} V2={:02X}", opcode, v1, v2);
+    }
+}
+
+#[test]
+fn load_6xnn_and_add_7xnn_without_carry_flag() {
+    let mut machine = Machine::builder().program(&[0x61F0, 0x7120]).build();
+    step(&mut machine, 2);
+    assert_eq!(machine.get_register(1), 0x10);
+    assert_eq!(machine.get_register(0xF), 0);
+}
+
+#[test]
+fn logic_8xy0_to_8xy3() {
+    let cases = [(0x8120, 0b0101), (0x8121, 0b1101), (0x8122, 0b0100), (0x8123, 0b1001)];
+    for (opcode, expected) in cases {
+        let mut machine = Machine::builder().reg(1, 0b1100).reg(2, 0b0101).reg(0xF, 7).program(&[opcode]).build();
+        step(&mut machine, 1);
+        assert_eq!(machine.get_register(1), expected, "{:04X}", opcode);
+        assert_eq!(machine.get_register(0xF), 7, "{:04X} must not touch VF", opcode);
+    }
+}
+
+#[test]
+fn logic_resets_vf_with_the_chip8_quirk() {
+    let mut machine = Machine::builder().quirks(Quirks::chip8()).reg(0xF, 7).program(&[0x8121]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(0xF), 0);
+}
+
+#[test]
+fn add_8xy4_sets_carry() {
+    let mut machine = Machine::builder().reg(1, 0xF0).reg(2, 0x20).program(&[0x8124]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(1), 0x10);
+    assert_eq!(machine.get_register(0xF), 1);
+
+    let mut machine = Machine::builder().reg(1, 0x10).reg(2, 0x20).program(&[0x8124]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(1), 0x30);
+    assert_eq!(machine.get_register(0xF), 0);
+}
+
+#[test]
+fn sub_8xy5_sets_vf_when_there_is_no_borrow() {
+    let mut machine = Machine::builder().reg(1, 0x30).reg(2, 0x10).program(&[0x8125]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(1), 0x20);
+    assert_eq!(machine.get_register(0xF), 1);
+
+    let mut machine = Machine::builder().reg(1, 0x10).reg(2, 0x30).program(&[0x8125]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(1), 0xE0);
+    assert_eq!(machine.get_register(0xF), 0);
+
+    // EQUAL VALUES DO NOT BORROW
+    let mut machine = Machine::builder().reg(1, 0x10).reg(2, 0x10).program(&[0x8125]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(0xF), 1);
+}
+
+#[test]
+fn subn_8xy7_sets_vf_when_there_is_no_borrow() {
+    let mut machine = Machine::builder().reg(1, 0x10).reg(2, 0x30).program(&[0x8127]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(1), 0x20);
+    assert_eq!(machine.get_register(0xF), 1);
+
+    let mut machine = Machine::builder().reg(1, 0x30).reg(2, 0x10).program(&[0x8127]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(1), 0xE0);
+    assert_eq!(machine.get_register(0xF), 0);
+}
+
+#[test]
+fn flag_wins_when_vf_is_the_destination() {
+    // 8FY4, 8FY5, 8FY7, 8FY6 AND 8FYE: THE FLAG IS WRITTEN AFTER THE RESULT
+    let cases = [
+        (0x8F14, 0xFF, 0x01, 1),
+        (0x8F15, 0x01, 0x02, 0),
+        (0x8F17, 0x02, 0x01, 0),
+        (0x8F16, 0x03, 0x00, 1),
+        (0x8F1E, 0x7F, 0x00, 0),
+    ];
+    for (opcode, vf, v1, flag) in cases {
+        let mut machine = Machine::builder().reg(0xF, vf).reg(1, v1).program(&[opcode]).build();
+        step(&mut machine, 1);
+        assert_eq!(machine.get_register(0xF), flag, "{:04X}", opcode);
+    }
+}
+
+#[test]
+fn shifts_8xy6_8xye() {
+    let mut machine = Machine::builder().reg(1, 0b1000_0011).reg(2, 0).program(&[0x8126, 0x812E]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(1), 0b0100_0001);
+    assert_eq!(machine.get_register(0xF), 1);
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(1), 0b1000_0010);
+    assert_eq!(machine.get_register(0xF), 0);
+}
+
+#[test]
+fn shifts_use_vy_with_the_chip8_quirk() {
+    let mut machine = Machine::builder()
+        .quirks(Quirks::chip8())
+        .reg(1, 0xFF)
+        .reg(2, 0b0000_0100)
+        .program(&[0x8126])
+        .build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(1), 0b0000_0010);
+    assert_eq!(machine.get_register(0xF), 0);
+}
+
+#[test]
+fn load_i_annn_and_jump_bnnn() {
+    let mut machine = Machine::builder().reg(0, 0x10).reg(3, 0x20).program(&[0xA123, 0xB300]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_i(), 0x123);
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0x310);
+
+    let mut machine = Machine::builder().quirks(Quirks::schip()).reg(0, 0x10).reg(3, 0x20).program(&[0xB300]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0x320);
+}
+
+#[test]
+fn random_cxnn_is_masked_and_seeded() {
+    let run = |seed| {
+        let mut machine = Machine::builder().seed(seed).program(&[0xC10F, 0xC2FF]).build();
+        step(&mut machine, 2);
+        (machine.get_register(1), machine.get_register(2))
+    };
+    let (masked, _) = run(1);
+    assert_eq!(masked & 0xF0, 0);
+    assert_eq!(run(1), run(1));
+    assert_ne!(run(1), run(2));
+}
+
+#[test]
+fn draw_dxyn_sets_vf_on_collision() {
+    let mut machine = Machine::builder()
+        .reg(0, 4)
+        .reg(1, 2)
+        .i(0x300)
+        .mem(0x300, &[0b1100_0000, 0b0110_0000])
+        .program(&[0xD012, 0xD012])
+        .build();
+    step(&mut machine, 1);
+    assert_eq!(lit_pixels(&machine), 4);
+    assert_eq!(machine.get_display()[2 * 64 + 4], 1);
+    assert_eq!(machine.get_register(0xF), 0);
+    step(&mut machine, 1);
+    assert_eq!(lit_pixels(&machine), 0);
+    assert_eq!(machine.get_register(0xF), 1);
+}
+
+#[test]
+fn draw_dxyn_wraps_or_clips_at_the_edges() {
+    let build = |quirks| {
+        let mut machine = Machine::builder()
+            .quirks(quirks)
+            .reg(0, 62)
+            .reg(1, 31)
+            .i(0x300)
+            .mem(0x300, &[0xFF, 0xFF])
+            .program(&[0xD012])
+            .build();
+        step(&mut machine, 1);
+        machine
+    };
+    assert_eq!(lit_pixels(&build(Quirks::legacy())), 16);
+    assert_eq!(lit_pixels(&build(Quirks::chip8())), 2);
+}
+
+#[test]
+fn keys_ex9e_exa1() {
+    let cases = [(0xE19E, 1 << 5, 0x204), (0xE19E, 0, 0x202), (0xE1A1, 0, 0x204), (0xE1A1, 1 << 5, 0x202)];
+    for (opcode, keys, pc) in cases {
+        let mut machine = Machine::builder().reg(1, 5).keys(keys).program(&[opcode]).build();
+        step(&mut machine, 1);
+        assert_eq!(machine.get_pc(), pc, "{:04X} with keys {:04X}", opcode, keys);
+    }
+}
+
+#[test]
+fn wait_key_fx0a() {
+    let mut machine = Machine::builder().program(&[0xF30A]).build();
+    step(&mut machine, 3);
+    assert_eq!(machine.get_pc(), 0x200);
+    machine.change_keys_pressed(1 << 0xB);
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0x202);
+    assert_eq!(machine.get_register(3), 0xB);
+}
+
+#[test]
+fn timers_fx07_fx15_fx18() {
+    let mut machine = Machine::builder().reg(1, 30).reg(2, 5).program(&[0xF115, 0xF218, 0xF307]).build();
+    step(&mut machine, 3);
+    assert_eq!(machine.get_delay_timer(), 30);
+    assert_eq!(machine.get_sound_timer(), 5);
+    assert_eq!(machine.get_register(3), 30);
+    assert!(machine.is_sound_active());
+}
+
+#[test]
+fn timers_tick_once_per_frame() {
+    let mut machine = Machine::builder().delay_timer(2).sound_timer(1).program(&[0x1200]).build();
+    machine.run_frame().unwrap();
+    assert_eq!(machine.get_delay_timer(), 1);
+    assert_eq!(machine.get_sound_timer(), 0);
+    assert!(!machine.is_sound_active());
+}
+
+#[test]
+fn add_i_fx1e_and_font_fx29() {
+    let mut machine = Machine::builder().i(0x300).reg(1, 0x10).reg(2, 0xA).program(&[0xF11E, 0xF229]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_i(), 0x310);
+    step(&mut machine, 1);
+    assert_eq!(machine.get_i(), 0xA * 5);
+    assert_eq!(machine.read_memory(machine.get_i()).unwrap(), 0xF0);
+}
+
+#[test]
+fn bcd_fx33() {
+    let mut machine = Machine::builder().i(0x300).reg(1, 254).program(&[0xF133]).build();
+    step(&mut machine, 1);
+    let digits: Vec<u8> = (0..3).map(|offset| machine.read_memory(0x300 + offset).unwrap()).collect();
+    assert_eq!(digits, [2, 5, 4]);
+}
+
+#[test]
+fn store_fx55_and_load_fx65() {
+    let mut machine = Machine::builder()
+        .regs([1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+        .i(0x300)
+        .program(&[0xF255, 0x6000, 0x6100, 0x6200, 0x6300, 0xF365])
+        .build();
+    step(&mut machine, 1);
+    assert_eq!(machine.read_memory(0x302).unwrap(), 3);
+    assert_eq!(machine.read_memory(0x303).unwrap(), 0);
+    assert_eq!(machine.get_i(), 0x300);
+    step(&mut machine, 5);
+    assert_eq!(&machine.get_registers()[..4], &[1, 2, 3, 0]);
+}
+
+#[test]
+fn memory_opcodes_increment_i_with_the_chip8_quirk() {
+    let mut machine = Machine::builder().quirks(Quirks::chip8()).i(0x300).program(&[0xF255, 0xF165]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_i(), 0x303);
+    step(&mut machine, 1);
+    assert_eq!(machine.get_i(), 0x305);
+}
+
+#[test]
+fn faults_return_errors_and_keep_pc() {
+    let mut machine = Machine::builder().program(&[0x5121, 0xE1FF]).pc(0x202).build();
+    assert_eq!(machine.run_instruction(), Err(Chip8Error::UnknownInstruction(0xE1FF)));
+    assert_eq!(machine.get_pc(), 0x202);
+
+    let mut machine = Machine::builder().program(&[0x00EE]).build();
+    assert_eq!(machine.run_instruction(), Err(Chip8Error::StackUnderflow));
+
+    let mut machine = Machine::builder().program(&[0x2200]).build();
+    let result = (0..100).try_for_each(|_| machine.run_instruction());
+    assert_eq!(result, Err(Chip8Error::StackOverflow));
+
+    let mut machine = Machine::builder().addressing(Addressing::Trap).i(0xFFE).program(&[0xF233]).build();
+    assert_eq!(machine.run_instruction(), Err(Chip8Error::AddressOutOfRange(0x1000)));
+    assert_eq!(machine.read_memory(0xFFE).unwrap(), 0);
+}
+
+#[test]
+fn rom_larger_than_memory_is_rejected() {
+    let mut machine = Machine::new();
+    assert_eq!(machine.load_rom(&[0; 4000]), Err(Chip8Error::RomTooLarge(4000)));
+}
+
+#[test]
+fn addresses_wrap_around_the_end_of_memory() {
+    let mut machine = Machine::builder().i(0xFFE).reg(1, 123).program(&[0xF133]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.read_memory(0xFFF).unwrap(), 2);
+    assert_eq!(machine.read_memory(0x000).unwrap(), 3);
+
+    // PC WRAPS TOO: A JUMP TO 0xFFE RUNS ITS INSTRUCTION, THEN CONTINUES AT 0x000
+    let mut machine = Machine::builder().pc(0xFFE).program(&[0x6105]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0x000);
+    assert_eq!(machine.get_current_opcode().unwrap(), 0xF090);
+
+    // SO DO A SKIP, A BXNN PAST THE END AND THE RETURN ADDRESS OF A CALL
+    let mut machine = Machine::builder().pc(0xFFE).program(&[0x3000]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0x002);
+    let mut machine = Machine::builder().reg(0, 0x10).program(&[0xBFF8]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0x008);
+    let mut machine = Machine::builder().pc(0xFFE).program(&[0x2300]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_stack(), [0x000]);
+
+    // WITH Trap, PC STAYS PAST THE END AND THE NEXT FETCH FAILS
+    let mut machine = Machine::builder().addressing(Addressing::Trap).pc(0xFFE).program(&[0x6105]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0x1000);
+    assert_eq!(machine.run_instruction(), Err(Chip8Error::AddressOutOfRange(0x1000)));
+}
+
+#[test]
+fn wrap16_has_64k_of_memory() {
+    let mut machine = Machine::builder()
+        .addressing(Addressing::Wrap16)
+        .i(0xFFFF)
+        .regs([1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+        .program(&[0xF155])
+        .build();
+    step(&mut machine, 1);
+    assert_eq!(machine.read_memory(0xFFFF).unwrap(), 1);
+    assert_eq!(machine.read_memory(0x0000).unwrap(), 2);
+    assert_eq!(machine.load_rom(&[0; 0x8000]), Ok(()));
+}
+
+#[test]
+fn add_i_fx1e_sets_vf_on_overflow_with_the_quirk() {
+    let quirks = Quirks { fx1e_sets_vf: true, ..Quirks::legacy() };
+    let mut machine = Machine::builder().quirks(quirks).i(0xFF0).reg(1, 0x20).program(&[0xF11E, 0xF11E]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_i(), 0x1010);
+    assert_eq!(machine.get_register(0xF), 1);
+
+    let mut machine = Machine::builder().quirks(quirks).i(0x100).reg(1, 0x20).reg(0xF, 1).program(&[0xF11E]).build();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(0xF), 0);
+}
+
+#[test]
+fn stack_depth_follows_the_config() {
+    let mut machine = Machine::builder().stack_config(Stack::vip()).program(&[0x2200]).build();
+    step(&mut machine, 12);
+    assert_eq!(machine.run_instruction(), Err(Chip8Error::StackOverflow));
+    assert_eq!(machine.get_stack().len(), 12);
+
+    let mut machine = Machine::builder().stack_config(Stack::schip()).program(&[0x2200]).build();
+    step(&mut machine, 16);
+    assert_eq!(machine.run_instruction(), Err(Chip8Error::StackOverflow));
+}
+
+#[test]
+fn vip_stack_lives_in_memory() {
+    let mut machine = Machine::builder()
+        .stack_config(Stack::vip())
+        .program(&[0x2300])
+        .mem(0x300, &[0x00, 0xEE])
+        .build();
+    step(&mut machine, 1);
+    assert_eq!(machine.read_memory(0xEA0).unwrap(), 0x02);
+    assert_eq!(machine.read_memory(0xEA1).unwrap(), 0x02);
+
+    // OVERWRITING THE STACK AREA CHANGES WHERE 00EE RETURNS
+    machine.write_memory(0xEA1, 0x40).unwrap();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_pc(), 0x240);
+}
+
+#[test]
+fn call_stack_lists_callers() {
+    let mut machine = Machine::builder().program(&[0x2300]).mem(0x300, &[0x24, 0x00]).build();
+    step(&mut machine, 2);
+    assert_eq!(
+        machine.get_call_stack(),
+        vec![
+            CallFrame { caller: 0x200, return_address: 0x202 },
+            CallFrame { caller: 0x300, return_address: 0x302 },
+        ]
+    );
+}
+
+#[test]
+fn vip_timing_charges_each_instruction() {
+    // 6XNN COSTS 74 CYCLES, SO 2615 CYCLES FIT 36 OF THEM
+    let mut machine = Machine::builder().timing(Timing::Vip).program(&[0x6001; 64]).build();
+    machine.run_frame().unwrap();
+    assert_eq!(machine.get_pc(), 0x200 + 2 * 36);
+
+    // 8XY4 IS SLOWER THAN 6XNN
+    let mut machine = Machine::builder().timing(Timing::Vip).program(&[0x8014; 64]).build();
+    machine.run_frame().unwrap();
+    assert_eq!(machine.get_pc(), 0x200 + 2 * 24);
+}
+
+#[test]
+fn instructions_run_counts_what_each_timing_ran() {
+    let mut machine = Machine::builder().program(&[0x6001; 64]).build();
+    machine.run_frame().unwrap();
+    assert_eq!(machine.get_instructions_run(), 10);
+
+    let mut machine = Machine::builder().timing(Timing::Vip).program(&[0x6001; 64]).build();
+    machine.run_frame().unwrap();
+    assert_eq!(machine.get_instructions_run(), 36);
+    step(&mut machine, 1);
+    assert_eq!(machine.get_instructions_run(), 37);
+}
+
+#[test]
+fn vip_timing_charges_a_skip_wrapping_around_memory() {
+    // 3 ANNN (240 CYCLES), THEN A SKIP FROM 0xFFE TO 0x002 (82 CYCLES WHEN TAKEN, 78 WHEN NOT)
+    // AND 6XNN (74 CYCLES) UNTIL THE 2615 CYCLES ARE SPENT: 31 OF THEM FIT ONLY IF THE SKIP
+    // WAS CHARGED AS TAKEN
+    let mut machine = Machine::builder()
+        .timing(Timing::Vip)
+        .pc(0xFF8)
+        .program(&[0xA000, 0xA000, 0xA000, 0x3000])
+        .mem(0x002, &[0x60, 0x01].repeat(40))
+        .build();
+    machine.run_frame().unwrap();
+    assert_eq!(machine.get_instructions_run(), 3 + 1 + 31);
+    assert_eq!(machine.get_pc(), 0x002 + 2 * 31);
+}
+
+#[test]
+fn vip_timing_draws_once_per_frame() {
+    let mut machine = Machine::builder().timing(Timing::Vip).program(&[0x6001, 0xD001, 0xD001, 0x1206]).build();
+    machine.run_frame().unwrap();
+    assert_eq!(machine.get_pc(), 0x202);
+    machine.run_frame().unwrap();
+    assert_eq!(machine.get_pc(), 0x204);
+    machine.run_frame().unwrap();
+    assert_eq!(machine.get_pc(), 0x206);
+}
+
+#[test]
+fn rewritten_instructions_are_decoded_again() {
+    let mut machine = Machine::builder().program(&[0x7201, 0x1200]).build();
+    step(&mut machine, 2);
+    // EITHER BYTE OF THE INSTRUCTION: 7301, THEN 7305
+    machine.write_memory(0x200, 0x73).unwrap();
+    step(&mut machine, 2);
+    machine.write_memory(0x201, 0x05).unwrap();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(2), 1);
+    assert_eq!(machine.get_register(3), 6);
+
+    // FX55 OVER THE NEXT INSTRUCTION: 6007 BECOMES 6009
+    let mut machine = Machine::builder().reg(0, 0x60).reg(1, 0x09).i(0x202).program(&[0xF155, 0x6007]).build();
+    step(&mut machine, 2);
+    assert_eq!(machine.get_register(0), 0x09);
+}
+
+#[test]
+fn instruction_wrapping_around_memory_is_decoded_again() {
+    // 6005 SPLIT BETWEEN 0xFFF AND 0x000
+    let mut machine = Machine::builder().pc(0xFFF).mem(0xFFF, &[0x60]).build();
+    machine.write_memory(0x000, 0x05).unwrap();
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(0), 0x05);
+    machine.write_memory(0x000, 0x07).unwrap();
+    machine.change_pc(0xFFF);
+    step(&mut machine, 1);
+    assert_eq!(machine.get_register(0), 0x07);
+}
+
+#[test]
+fn loading_a_state_keeps_the_decode_cache_switch() {
+    for decode_cache in [true, false] {
+        let config = Config { decode_cache, ..Config::default() };
+        let mut machine = Machine::builder().config(config).program(&[0x7201, 0x1200]).build();
+        let state = machine.save_state();
+        machine.load_state(&state).unwrap();
+        step(&mut machine, 1);
+        assert_eq!(machine.is_decoded(0x200), decode_cache);
+        assert_eq!(machine.get_config().decode_cache, decode_cache);
+    }
+}
+
+#[test]
+fn rows_past_the_screen_are_never_dirty() {
+    let mut machine = Machine::builder().program(&[0x00E0]).build();
+    step(&mut machine, 1);
+    assert!(machine.is_display_row_dirty(31));
+    assert!(!machine.is_display_row_dirty(32));
+    assert!(!machine.is_display_row_dirty(usize::MAX));
+}
@@ -0,0 +1,75 @@
+use chip8_emulator::Machine;
+use chip8_emulator::profiler::{self, SubroutineStats};
+
+fn step(machine: &mut Machine, count: usize) {
+    for _ in 0..count {
+        machine.run_instruction().unwrap();
+    }
+}
+
+// SETS THE DELAY TIMER, CALLS 0x210 WHICH CALLS 0x214, THEN WAITS FOR THE DELAY TIMER
+const PROGRAM: [u8; 24] = [
+    0x61, 0x03, 0xF1, 0x15, 0x22, 0x10, // 200: V1 = 3, DT = V1, CALL 210
+    0xF0, 0x07, 0x30, 0x00, 0x12, 0x06, // 206: V0 = DT, SKIP IF V0 == 0, JUMP 206
+    0x12, 0x0C, 0x00, 0x00, // 20C: JUMP 20C
+    0x22, 0x14, 0x00, 0xEE, // 210: CALL 214, RETURN
+    0x62, 0x01, 0x00, 0xEE, // 214: V2 = 1, RETURN
+];
+
+fn profiled_run() -> Machine {
+    let mut machine = Machine::new();
+    machine.load_rom(&PROGRAM).unwrap();
+    machine.start_profiler();
+    // THE TIMERS ONLY TICK BETWEEN FRAMES, THE WAIT LOOPS UNTIL THE TIMER IS CLEARED
+    step(&mut machine, 7 + 5 * 3);
+    machine.change_delay_timer(0);
+    step(&mut machine, 3);
+    machine
+}
+
+#[test]
+fn instructions_are_counted_per_address_and_opcode() {
+    let machine = profiled_run();
+    let profiler = machine.get_profiler().unwrap();
+    assert_eq!(profiler.get_total(), 25);
+    assert_eq!(profiler.get_hits(0x206), 6);
+    assert_eq!(profiler.get_hits(0x20E), 0);
+    assert_eq!(profiler.hottest(1), vec![(0x206, 6)]);
+    assert!(profiler.classes().contains(&("1NNN", 6)));
+    assert_eq!(profiler::opcode_class(0x8AB4), "8XY4");
+    assert_eq!(profiler::opcode_class(0xF0FF), "????");
+}
+
+#[test]
+fn subroutines_are_timed_with_their_callees() {
+    let machine = profiled_run();
+    let profiler = machine.get_profiler().unwrap();
+    assert_eq!(
+        profiler.subroutines(),
+        vec![
+            SubroutineStats { address: 0x210, calls: 1, self_instructions: 2, total_instructions: 4 },
+            SubroutineStats { address: 0x214, calls: 1, self_instructions: 2, total_instructions: 2 },
+        ]
+    );
+    assert_eq!(profiler.folded_stacks(), "main 21\nmain;sub_0210 2\nmain;sub_0210;sub_0214 2\n");
+}
+
+#[test]
+fn delay_timer_waits_are_detected() {
+    let mut machine = profiled_run();
+    assert_eq!(machine.get_profiler().unwrap().busy_waits(), vec![(0x206, 5)]);
+    assert!(machine.stop_profiler().unwrap().report().contains("0206           5 iterations"));
+    assert!(machine.get_profiler().is_none());
+}
+
+#[test]
+fn profiling_can_start_inside_a_subroutine() {
+    let mut machine = Machine::new();
+    machine.load_rom(&PROGRAM).unwrap();
+    // STOPPED ON THE RETURN OF 0x214, CALLED FROM 0x210
+    step(&mut machine, 5);
+    machine.start_profiler();
+    step(&mut machine, 2);
+    let profiler = machine.get_profiler().unwrap();
+    assert_eq!(profiler.folded_stacks(), "main;sub_0210 1\nmain;sub_0210;sub_0214 1\n");
+}
@@ -0,0 +1,94 @@
+use std::env;
+use std::fs;
+use chip8_emulator::reload::{self, ReloadOptions};
+use chip8_emulator::Machine;
+
+// 200: V0 += 1, JUMP 200
+const OLD_ROM: [u8; 4] = [0x70, 0x01, 0x12, 0x00];
+// 200: V1 += 1, JUMP 200
+const NEW_ROM: [u8; 4] = [0x71, 0x01, 0x12, 0x00];
+
+fn old_machine() -> Machine {
+    let mut machine = Machine::builder().i(0x345).mem(0x300, &[1, 2, 3, 4]).build();
+    machine.load_rom(&OLD_ROM).unwrap();
+    machine.run_frame().unwrap();
+    machine
+}
+
+#[test]
+fn reload_starts_the_new_rom_afresh() {
+    let old = old_machine();
+    let mut reloaded = reload::reload(&old, &NEW_ROM, &ReloadOptions::default(), 1).unwrap();
+    assert_eq!(reloaded.get_pc(), 0x200);
+    assert_eq!(reloaded.get_registers(), &[0; 16]);
+    assert_eq!(reloaded.get_i(), 0);
+    assert_eq!(reloaded.read_memory(0x300).unwrap(), 0);
+    reloaded.run_frame().unwrap();
+    assert_eq!(reloaded.get_register(0), 0);
+    assert_eq!(reloaded.get_register(1), 5);
+}
+
+#[test]
+fn reload_keeps_the_registers() {
+    let old = old_machine();
+    let options = ReloadOptions { keep_registers: true, ..ReloadOptions::default() };
+    let reloaded = reload::reload(&old, &NEW_ROM, &options, 1).unwrap();
+    assert_eq!(reloaded.get_register(0), 5);
+    assert_eq!(reloaded.get_i(), 0x345);
+    // PC AND THE MEMORY ARE STILL THE NEW MACHINE'S
+    assert_eq!(reloaded.get_pc(), 0x200);
+    assert_eq!(reloaded.read_memory(0x300).unwrap(), 0);
+}
+
+#[test]
+fn reload_keeps_the_ram_ranges() {
+    let old = old_machine();
+    let options = ReloadOptions { keep_ram: vec![(0x300, 0x301), (0x303, 0x303)], ..ReloadOptions::default() };
+    let reloaded = reload::reload(&old, &NEW_ROM, &options, 1).unwrap();
+    let kept: Vec<u8> = (0x300..0x304).map(|address| reloaded.read_memory(address).unwrap()).collect();
+    assert_eq!(kept, [1, 2, 0, 4]);
+    assert_eq!(reloaded.get_register(0), 0);
+
+    // A RANGE OVER THE PROGRAM TAKES THE OLD CODE BACK
+    let options = ReloadOptions { keep_ram: vec![(0x200, 0x201)], ..ReloadOptions::default() };
+    let reloaded = reload::reload(&old, &NEW_ROM, &options, 1).unwrap();
+    assert_eq!(reloaded.get_current_opcode().unwrap(), 0x7001);
+}
+
+#[test]
+fn reload_loads_the_state_under_the_new_rom() {
+    let old = old_machine();
+    let path = env::temp_dir().join(format!("chip8-reload-{}.state", std::process::id()));
+    fs::write(&path, old.save_state()).unwrap();
+    let options = ReloadOptions { state: Some(path.clone()), ..ReloadOptions::default() };
+    let reloaded = reload::reload(&old, &NEW_ROM, &options, 1);
+    fs::remove_file(&path).unwrap();
+    let reloaded = reloaded.unwrap();
+    assert_eq!(reloaded.get_register(0), 5);
+    assert_eq!(reloaded.read_memory(0x300).unwrap(), 1);
+    assert_eq!(reloaded.get_current_opcode().unwrap(), 0x7101);
+
+    let options = ReloadOptions { state: Some(path.clone()), ..ReloadOptions::default() };
+    let error = reload::reload(&old, &NEW_ROM, &options, 1).err().unwrap();
+    assert!(error.starts_with(&path.display().to_string()), "{}", error);
+}
+
+#[test]
+fn reload_reports_a_rom_that_does_not_fit() {
+    let old = old_machine();
+    assert!(reload::reload(&old, &[0; 0x1000], &ReloadOptions::default(), 1).is_err());
+}
+
+#[test]
+fn ranges_are_parsed_in_hexadecimal() {
+    assert_eq!(reload::parse_range("300-3FF"), Some((0x300, 0x3FF)));
+    assert_eq!(reload::parse_range("3ff-3ff"), Some((0x3FF, 0x3FF)));
+    assert_eq!(reload::parse_range("EA0"), Some((0xEA0, 0xEA0)));
+}
+
+#[test]
+fn invalid_ranges_are_rejected() {
+    for text in ["", "-", "3FF-300", "300-", "-3FF", "300-3FG", "XYZ", "10000", "300-3FF-400"] {
+        assert_eq!(reload::parse_range(text), None, "{:?}", text);
+    }
+}
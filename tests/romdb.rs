@@ -0,0 +1,21 @@
+use std::fs;
+use chip8_emulator::romdb::{self, Platform};
+
+#[test]
+fn bundled_roms_are_in_the_database() {
+    for entry in fs::read_dir("data").unwrap() {
+        let path = entry.unwrap().path();
+        let data = fs::read(&path).unwrap();
+        let info = romdb::lookup(&data).unwrap_or_else(|| panic!("{} is not in the database", path.display()));
+        assert_eq!(info.platform, Platform::Chip8);
+    }
+    assert_eq!(romdb::lookup(&[0x12, 0x00]), None);
+}
+
+#[test]
+fn platform_is_detected_from_the_opcodes() {
+    assert_eq!(romdb::detect_platform(&[0x60, 0x01, 0x12, 0x00]), Platform::Chip8);
+    assert_eq!(romdb::detect_platform(&[0x00, 0xFF, 0x12, 0x02]), Platform::Schip);
+    assert_eq!(romdb::detect_platform(&[0x00, 0xFF, 0xF0, 0x00, 0x12, 0x34]), Platform::XoChip);
+    assert_eq!(romdb::detect_platform(&[0; 4000]), Platform::XoChip);
+}
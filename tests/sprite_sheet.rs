@@ -0,0 +1,33 @@
+use chip8_emulator::sprite_sheet::{SpriteFormat, SpriteSheet};
+
+#[test]
+fn sprites_are_laid_out_in_a_grid() {
+    // THE "0" AND "1" DIGITS OF THE FONT
+    let memory = [0xF0, 0x90, 0x90, 0x90, 0xF0, 0x20, 0x60, 0x20, 0x20, 0x70];
+    let sheet = SpriteSheet::render(&memory, 0, 2, SpriteFormat::Chip8 { height: 5 }, 2);
+    assert_eq!((sheet.width, sheet.height), (17, 5));
+    assert!(sheet.is_lit(0, 0) && sheet.is_lit(3, 0) && !sheet.is_lit(4, 0));
+    assert!(!sheet.is_lit(8, 0));
+    assert!(sheet.is_lit(9 + 2, 0) && sheet.is_lit(9 + 1, 4));
+}
+
+#[test]
+fn schip_sprites_are_16_pixels_wide() {
+    let mut memory = [0; 32];
+    memory[0] = 0x80;
+    memory[31] = 0x01;
+    let sheet = SpriteSheet::render(&memory, 0, 1, SpriteFormat::Schip16, 1);
+    assert_eq!((sheet.width, sheet.height), (16, 16));
+    assert!(sheet.is_lit(0, 0) && sheet.is_lit(15, 15));
+    assert_eq!(sheet.pixels.iter().filter(|lit| **lit).count(), 2);
+}
+
+#[test]
+fn png_export_has_the_right_header() {
+    let sheet = SpriteSheet::render(&[0xFF], 0, 1, SpriteFormat::Chip8 { height: 1 }, 1);
+    let png = sheet.to_png(2);
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(&png[16..24], &[0, 0, 0, 16, 0, 0, 0, 2]);
+    assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]));
+}